@@ -0,0 +1,453 @@
+use crate::outline::Outline;
+use crate::overlay::{overlay, segments, OverlayPoint, Segment};
+use crate::polygon::Polygon;
+use glam::Vec2;
+
+/// Boolean set operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Points inside of any operand.
+    Union,
+    /// Points inside of both operands.
+    Intersection,
+    /// Points inside of first operand, but not inside of second.
+    Difference,
+    /// Points inside of exactly one operand.
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Xor => a != b,
+        }
+    }
+}
+
+fn boolean<P: OverlayPoint>(segments: &[Segment<P>], op: BooleanOp) -> Vec<Polygon<P>> {
+    overlay(segments, |winding| {
+        op.apply(winding[0] != 0, winding[1] != 0)
+    })
+}
+
+/// Boolean methods for point types supported by overlay. Every decision is exact for float
+/// points and for integer points not greater than `2^53` by magnitude, only intersection points
+/// are rounded to the nearest representable point.
+macro_rules! boolean_methods {
+    ($($point:ty),*) => {$(
+        impl Polygon<$point> {
+            /// Boolean set operation with `other` polygon.
+            /// Returns disjoint polygons with holes. Shared edges and coincident vertices of
+            /// operands are merged.
+            pub fn boolean(&self, other: &Self, op: BooleanOp) -> Vec<Self> {
+                let segments: Vec<Segment<$point>> =
+                    segments(self, 0).chain(segments(other, 1)).collect();
+                boolean(&segments, op)
+            }
+
+            /// Region covered by any of polygons. See [`Polygon::boolean`].
+            pub fn union(&self, other: &Self) -> Vec<Self> {
+                self.boolean(other, BooleanOp::Union)
+            }
+
+            /// Region covered by both polygons. See [`Polygon::boolean`].
+            pub fn intersection(&self, other: &Self) -> Vec<Self> {
+                self.boolean(other, BooleanOp::Intersection)
+            }
+
+            /// Region of this polygon not covered by `other`. See [`Polygon::boolean`].
+            pub fn difference(&self, other: &Self) -> Vec<Self> {
+                self.boolean(other, BooleanOp::Difference)
+            }
+
+            /// Region covered by exactly one of polygons. See [`Polygon::boolean`].
+            pub fn xor(&self, other: &Self) -> Vec<Self> {
+                self.boolean(other, BooleanOp::Xor)
+            }
+        }
+
+        impl Outline<$point> {
+            /// Boolean set operation with `other` outline. Both outlines **MUST** follow
+            /// [`Outline::new`] orientation contract.
+            /// Returns disjoint polygons with holes.
+            pub fn boolean(&self, other: &Self, op: BooleanOp) -> Vec<Polygon<$point>> {
+                let edges = |outline: &Self, owner: usize| {
+                    outline
+                        .edges()
+                        .map(move |(from, to)| (from, to, owner))
+                        .collect::<Vec<_>>()
+                };
+                let mut segments = edges(self, 0);
+                segments.extend(edges(other, 1));
+                boolean(&segments, op)
+            }
+
+            /// Region covered by any of outlines. See [`Outline::boolean`].
+            pub fn union(&self, other: &Self) -> Vec<Polygon<$point>> {
+                self.boolean(other, BooleanOp::Union)
+            }
+
+            /// Region covered by both outlines. See [`Outline::boolean`].
+            pub fn intersection(&self, other: &Self) -> Vec<Polygon<$point>> {
+                self.boolean(other, BooleanOp::Intersection)
+            }
+
+            /// Region of this outline not covered by `other`. See [`Outline::boolean`].
+            pub fn difference(&self, other: &Self) -> Vec<Polygon<$point>> {
+                self.boolean(other, BooleanOp::Difference)
+            }
+
+            /// Region covered by exactly one of outlines. See [`Outline::boolean`].
+            pub fn xor(&self, other: &Self) -> Vec<Polygon<$point>> {
+                self.boolean(other, BooleanOp::Xor)
+            }
+        }
+    )*};
+}
+
+boolean_methods!(Vec2, [f32; 2], [f64; 2], [i32; 2], [i64; 2]);
+
+#[cfg(test)]
+mod tests {
+    use super::BooleanOp;
+    use crate::outline::Outline;
+    use crate::point::{Point, Scalar};
+    use crate::polygon::Polygon;
+    use crate::sweep::{intersections, EdgeRef};
+    use crate::testing::Random;
+    use glam::Vec2;
+    use std::cmp::Ordering;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Outline {
+        let verts = vec![
+            Vec2::new(x, y),
+            Vec2::new(x + w, y),
+            Vec2::new(x + w, y + h),
+            Vec2::new(x, y + h),
+        ];
+        Outline::new(verts.into_iter())
+    }
+
+    fn area(polygons: &[Polygon]) -> f32 {
+        polygons.iter().map(Polygon::area).sum()
+    }
+
+    fn segments_cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+        let orient = |p: Vec2, q: Vec2, r: Vec2| (q - p).perp_dot(r - p);
+        let (d1, d2) = (orient(a, b, c), orient(a, b, d));
+        let (d3, d4) = (orient(c, d, a), orient(c, d, b));
+        d1 * d2 <= 0f32 && d3 * d4 <= 0f32
+    }
+
+    fn is_simple(outline: &Outline) -> bool {
+        let len = outline.len() as isize;
+        for i in 0..len {
+            for j in i + 2..len {
+                if i == 0 && j == len - 1 {
+                    continue;
+                }
+                if segments_cross(outline[i], outline[i + 1], outline[j], outline[j + 1]) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Star-shaped outline with vertices on grid with `step`. Integer step makes operands often
+    /// share vertices and overlap along edges.
+    fn random_outline(random: &mut Random, step: f32) -> Outline {
+        loop {
+            let count = random.range(3, 10);
+            let (cx, cy) = (random.range(-5, 5) as f32, random.range(-5, 5) as f32);
+            let outline = Outline::new((0..count).map(|i| {
+                let angle = i as f32 / count as f32 * 2f32 * std::f32::consts::PI;
+                let radius = random.range(2, 10) as f32;
+                Vec2::new(
+                    (cx + angle.cos() * radius).round() * step,
+                    (cy + angle.sin() * radius).round() * step,
+                )
+            }));
+            if outline.signed_area() > 0f32 && is_simple(&outline) {
+                return outline;
+            }
+        }
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        let tolerance = 1e-3f32 * expected.abs().max(1f32);
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn overlapping_rects() {
+        let a = rect(0f32, 0f32, 2f32, 2f32);
+        let b = rect(1f32, 1f32, 2f32, 2f32);
+        assert_eq!(area(&a.union(&b)), 7f32);
+        assert_eq!(area(&a.intersection(&b)), 1f32);
+        assert_eq!(area(&a.difference(&b)), 3f32);
+        assert_eq!(area(&a.xor(&b)), 6f32);
+        assert_eq!(a.union(&b)[0].outline().len(), 8);
+    }
+
+    #[test]
+    fn shared_edge() {
+        let a = rect(0f32, 0f32, 1f32, 1f32);
+        let b = rect(1f32, 0f32, 1f32, 1f32);
+        let union = a.union(&b);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].outline().len(), 4);
+        assert_eq!(union[0].area(), 2f32);
+        assert!(a.intersection(&b).is_empty());
+        assert_eq!(a.difference(&b), vec![Polygon::without_holes(a).unwrap()]);
+    }
+
+    #[test]
+    fn identical() {
+        let a = rect(0f32, 0f32, 1f32, 1f32);
+        assert_eq!(area(&a.union(&a)), 1f32);
+        assert_eq!(area(&a.intersection(&a)), 1f32);
+        assert!(a.difference(&a).is_empty());
+        assert!(a.xor(&a).is_empty());
+    }
+
+    #[test]
+    fn coincident_vertex() {
+        let a = rect(0f32, 0f32, 1f32, 1f32);
+        let b = rect(1f32, 1f32, 1f32, 1f32);
+        let union = a.union(&b);
+        assert_eq!(union.len(), 2);
+        assert!(a.intersection(&b).is_empty());
+    }
+
+    #[test]
+    fn hole_is_produced() {
+        let wall = rect(0f32, 0f32, 4f32, 4f32);
+        let door = rect(1f32, 1f32, 2f32, 2f32);
+        let result = wall.difference(&door);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].holes().len(), 1);
+        assert_eq!(result[0].area(), 12f32);
+
+        let polygon = result[0].clone();
+        let restored = polygon.union(&Polygon::without_holes(door).unwrap());
+        assert_eq!(restored, vec![Polygon::without_holes(wall).unwrap()]);
+    }
+
+    #[test]
+    fn polygons_with_holes() {
+        let a = Polygon::new(
+            rect(0f32, 0f32, 6f32, 6f32),
+            vec![Outline::new(
+                rect(2f32, 2f32, 2f32, 2f32)
+                    .vertices()
+                    .iter()
+                    .rev()
+                    .copied(),
+            )],
+        )
+        .unwrap();
+        let b = Polygon::without_holes(rect(3f32, 3f32, 6f32, 6f32)).unwrap();
+        assert_eq!(area(&a.union(&b)), 32f32 + 36f32 - 8f32);
+        assert_eq!(area(&a.intersection(&b)), 8f32);
+        assert_eq!(area(&a.difference(&b)), 24f32);
+        assert_eq!(area(&a.xor(&b)), 32f32 + 36f32 - 16f32);
+    }
+
+    fn check_area_identities(seed: u64, step: f32) {
+        let mut random = Random(seed);
+        for _ in 0..300 {
+            let a = random_outline(&mut random, step);
+            let b = random_outline(&mut random, step);
+            let (area_a, area_b) = (a.signed_area(), b.signed_area());
+            let union = area(&a.boolean(&b, BooleanOp::Union));
+            let intersection = area(&a.boolean(&b, BooleanOp::Intersection));
+            let difference = area(&a.boolean(&b, BooleanOp::Difference));
+            let reversed = area(&b.boolean(&a, BooleanOp::Difference));
+            let xor = area(&a.boolean(&b, BooleanOp::Xor));
+
+            assert_close(union, area_a + area_b - intersection);
+            assert_close(difference, area_a - intersection);
+            assert_close(reversed, area_b - intersection);
+            assert_close(xor, union - intersection);
+            assert!(intersection <= area_a.min(area_b) + 1e-3f32);
+        }
+    }
+
+    #[test]
+    fn random_area_identities() {
+        check_area_identities(0x2545_f491, 1f32);
+    }
+
+    #[test]
+    fn random_area_identities_off_grid() {
+        check_area_identities(0x9e37_79b9, 0.37f32);
+    }
+
+    /// Doubled area of `polygons` translated by `-base`, so large offsets don't cancel.
+    fn doubled_area<P: Point>(polygons: &[Polygon<P>], base: f64) -> f64 {
+        polygons
+            .iter()
+            .flat_map(Polygon::rings)
+            .map(|ring| {
+                let shifted = ring.map(|p| [p.x().to_f64() - base, p.y().to_f64() - base]);
+                shifted.doubled_signed_area()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn every_point_type() {
+        macro_rules! check {
+            ($($point:ty),*) => {$({
+                let convert = |outline: Outline| {
+                    outline.map(|p| {
+                        let coordinate = |value: f32| Scalar::from_f64(f64::from(value));
+                        <$point as Point>::from_xy(coordinate(p.x()), coordinate(p.y()))
+                    })
+                };
+                let a = convert(rect(0f32, 0f32, 2f32, 2f32));
+                let b = convert(rect(1f32, 1f32, 2f32, 2f32));
+                assert_eq!(doubled_area(&a.union(&b), 0f64), 14f64);
+                assert_eq!(doubled_area(&a.intersection(&b), 0f64), 2f64);
+                assert_eq!(doubled_area(&a.difference(&b), 0f64), 6f64);
+                assert_eq!(doubled_area(&a.xor(&b), 0f64), 12f64);
+
+                let wall = convert(rect(0f32, 0f32, 4f32, 4f32));
+                let door = convert(rect(1f32, 1f32, 2f32, 2f32));
+                let framed = wall.difference(&door);
+                assert_eq!(framed.len(), 1);
+                assert_eq!(framed[0].holes().len(), 1);
+                assert_eq!(doubled_area(&framed, 0f64), 24f64);
+            })*};
+        }
+        check!(Vec2, [f32; 2], [f64; 2], [i32; 2], [i64; 2]);
+    }
+
+    #[test]
+    fn f64_area_identities() {
+        // Offset beyond `f32` precision of grid doesn't affect `f64` operands.
+        let base = 1e5f64;
+        let mut random = Random(0x0bad_cafe);
+        for _ in 0..200 {
+            let shift = |outline: Outline| {
+                outline.map(|p| [base + f64::from(p.x()), base + f64::from(p.y())])
+            };
+            let a = shift(random_outline(&mut random, 0.37f32));
+            let b = shift(random_outline(&mut random, 0.37f32));
+            let single = |outline: &Outline<[f64; 2]>| {
+                outline
+                    .map(|p| [p[0] - base, p[1] - base])
+                    .doubled_signed_area()
+            };
+            let (area_a, area_b) = (single(&a), single(&b));
+            let union = doubled_area(&a.union(&b), base);
+            let intersection = doubled_area(&a.intersection(&b), base);
+            let close = |actual: f64, expected: f64| {
+                assert!(
+                    (actual - expected).abs() <= 1e-3f64,
+                    "{} != {}",
+                    actual,
+                    expected
+                )
+            };
+            close(union, area_a + area_b - intersection);
+            close(doubled_area(&a.difference(&b), base), area_a - intersection);
+            close(doubled_area(&a.xor(&b), base), union - intersection);
+        }
+    }
+
+    fn integer_area(polygons: &[Polygon<[i64; 2]>]) -> i128 {
+        polygons
+            .iter()
+            .flat_map(Polygon::rings)
+            .map(Outline::doubled_signed_area)
+            .sum()
+    }
+
+    #[test]
+    fn integer_rects() {
+        // Offset beyond `f32` precision doesn't affect integer operands.
+        let base = 1i64 << 40;
+        let rect = |x: i64, y: i64, size: i64| {
+            let verts = vec![[x, y], [x + size, y], [x + size, y + size], [x, y + size]];
+            Outline::new(verts.into_iter().map(|[x, y]| [base + x, base + y]))
+        };
+        let (a, b) = (rect(0, 0, 3), rect(1, 1, 3));
+        assert_eq!(integer_area(&a.union(&b)), 2 * 14);
+        assert_eq!(integer_area(&a.intersection(&b)), 2 * 4);
+        assert_eq!(integer_area(&a.difference(&b)), 2 * 5);
+        assert_eq!(integer_area(&a.xor(&b)), 2 * 10);
+
+        let polygon = Polygon::without_holes(rect(0, 0, 5)).unwrap();
+        let inner = Polygon::without_holes(rect(1, 1, 3)).unwrap();
+        let framed = polygon.difference(&inner);
+        assert_eq!(framed.len(), 1);
+        assert_eq!(framed[0].holes().len(), 1);
+        assert_eq!(framed[0].holes()[0].doubled_signed_area(), -2 * 9);
+    }
+
+    #[test]
+    fn integer_area_identities() {
+        let mut random = Random(0x1234_5678);
+        for _ in 0..200 {
+            let a = random_outline(&mut random, 1f32).to_i64(1000f32).unwrap();
+            let b = random_outline(&mut random, 1f32).to_i64(1000f32).unwrap();
+            let (area_a, area_b) = (a.doubled_signed_area(), b.doubled_signed_area());
+            let union = a.union(&b);
+            let intersection = integer_area(&a.intersection(&b));
+            assert_eq!(union, a.union(&b));
+
+            // Only intersection points are rounded, by half unit at most.
+            let tolerance = 2 * 100_000;
+            let close = |actual: i128, expected: i128| {
+                assert!(
+                    (actual - expected).abs() <= tolerance,
+                    "{} != {}",
+                    actual,
+                    expected
+                )
+            };
+            close(integer_area(&union), area_a + area_b - intersection);
+            close(integer_area(&a.difference(&b)), area_a - intersection);
+            close(
+                integer_area(&a.xor(&b)),
+                integer_area(&union) - intersection,
+            );
+            assert_eq!(integer_area(&a.union(&a)), area_a);
+        }
+    }
+
+    #[test]
+    fn rounded_crossings_stay_simple() {
+        // Coarse grid, so rounded crossings often move pieces of edges across other edges.
+        let mut random = Random(0xdead_beef);
+        for _ in 0..300 {
+            let a = random_outline(&mut random, 1f32).to_i64(3f32).unwrap();
+            let b = random_outline(&mut random, 1f32).to_i64(3f32).unwrap();
+            for &op in &[BooleanOp::Union, BooleanOp::Intersection, BooleanOp::Xor] {
+                let result = a.boolean(&b, op);
+                let rings: Vec<&Outline<[i64; 2]>> =
+                    result.iter().flat_map(Polygon::rings).collect();
+                for found in intersections(rings.iter().copied()) {
+                    let (e, f) = found.edges;
+                    let edge = |r: EdgeRef| rings[r.outline].edges().nth(r.edge).unwrap();
+                    let ((p, q), (r, s)) = (edge(e), edge(f));
+                    let opposite =
+                        |x: Ordering, y: Ordering| x != Ordering::Equal && x == y.reverse();
+                    let crossing = opposite(Point::orient(p, q, r), Point::orient(p, q, s))
+                        && opposite(Point::orient(r, s, p), Point::orient(r, s, q));
+                    assert!(!crossing, "{:?} crosses {:?}", (p, q), (r, s));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,513 @@
+//! Bounding volumes of outlines: axis-aligned box, the smallest oriented rectangles and the
+//! smallest enclosing circle.
+//!
+//! Bounds depend only on vertices, so outlines may be self-intersecting.
+use crate::hull::convex_hull;
+use crate::outline::Outline;
+use crate::point::Point;
+use glam::Vec2;
+
+type Xy = [f64; 2];
+
+fn xy(p: Vec2) -> Xy {
+    [f64::from(p.x()), f64::from(p.y())]
+}
+
+fn dot(a: Xy, b: Xy) -> f64 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn distance_squared(a: Xy, b: Xy) -> f64 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    dx * dx + dy * dy
+}
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb<P = Vec2> {
+    /// Corner with the least coordinates.
+    pub min: P,
+    /// Corner with the greatest coordinates.
+    pub max: P,
+}
+
+impl<P: Point> Aabb<P> {
+    /// Test if `point` is inside of box or on its boundary.
+    pub fn contains(&self, point: P) -> bool {
+        self.min.x() <= point.x()
+            && point.x() <= self.max.x()
+            && self.min.y() <= point.y()
+            && point.y() <= self.max.y()
+    }
+
+    /// Corners in counter-clockwise order starting at `min`.
+    pub fn corners(&self) -> [P; 4] {
+        [
+            self.min,
+            P::from_xy(self.max.x(), self.min.y()),
+            self.max,
+            P::from_xy(self.min.x(), self.max.y()),
+        ]
+    }
+}
+
+impl Aabb {
+    /// Width and height of box.
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Area of box.
+    pub fn area(&self) -> f32 {
+        let size = self.size();
+        size.x() * size.y()
+    }
+}
+
+impl<P: Point> From<Aabb<P>> for Outline<P> {
+    fn from(aabb: Aabb<P>) -> Self {
+        Outline::new(aabb.corners().iter().copied())
+    }
+}
+
+/// Rectangle rotated by arbitrary angle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedRect {
+    /// Center of rectangle.
+    pub center: Vec2,
+    /// Unit direction of sides with length `width`.
+    pub axis: Vec2,
+    /// Length of sides along `axis`.
+    pub width: f32,
+    /// Length of sides across `axis`.
+    pub height: f32,
+}
+
+impl OrientedRect {
+    /// Corners in counter-clockwise order.
+    pub fn corners(&self) -> [Vec2; 4] {
+        let along = self.axis * (self.width * 0.5f32);
+        let across = Vec2::new(-self.axis.y(), self.axis.x()) * (self.height * 0.5f32);
+        [
+            self.center - along - across,
+            self.center + along - across,
+            self.center + along + across,
+            self.center - along + across,
+        ]
+    }
+
+    /// Area of rectangle.
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    /// Total length of rectangle sides.
+    pub fn perimeter(&self) -> f32 {
+        2f32 * (self.width + self.height)
+    }
+}
+
+impl From<OrientedRect> for Outline {
+    fn from(rect: OrientedRect) -> Self {
+        Outline::new(rect.corners().iter().copied())
+    }
+}
+
+/// Circle with center and radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    /// Center of circle.
+    pub center: Vec2,
+    /// Radius of circle.
+    pub radius: f32,
+}
+
+impl Circle {
+    /// Area of circle.
+    pub fn area(&self) -> f32 {
+        std::f32::consts::PI * self.radius * self.radius
+    }
+
+    /// Test if `point` is inside of circle or on its boundary.
+    pub fn contains(&self, point: Vec2) -> bool {
+        distance_squared(xy(self.center), xy(point)) <= f64::from(self.radius).powi(2)
+    }
+
+    /// Counter-clockwise regular polygon around circle, so it contains whole circle.
+    /// # Arguments
+    /// * `vertices` - number of vertices. **MUST** be at least 3;
+    pub fn to_outline(&self, vertices: usize) -> Outline {
+        let step = std::f64::consts::PI * 2f64 / vertices as f64;
+        let radius = f64::from(self.radius) / (step * 0.5f64).cos();
+        Outline::new((0..vertices).map(|i| {
+            let angle = step * i as f64;
+            let offset = Vec2::new(angle.cos() as f32, angle.sin() as f32);
+            self.center + offset * radius as f32
+        }))
+    }
+}
+
+/// Index after `i`, while `f` grows along convex `hull`.
+fn advance(hull: &[Xy], mut i: usize, f: impl Fn(Xy) -> f64) -> usize {
+    for _ in 0..hull.len() {
+        let next = (i + 1) % hull.len();
+        if f(hull[next]) <= f(hull[i]) {
+            break;
+        }
+        i = next;
+    }
+    i
+}
+
+/// The oriented rectangle around `points` with the least `cost` of width and height by
+/// rotating calipers. One side of such rectangle lies on edge of convex hull.
+fn calipers(points: &[Vec2], cost: impl Fn(f64, f64) -> f64) -> Option<OrientedRect> {
+    let hull = convex_hull(points.iter().copied());
+    let origin = *hull.vertices().first()?;
+    let hull: Vec<Xy> = hull.vertices().iter().map(|&p| xy(p - origin)).collect();
+    let n = hull.len();
+    if n == 1 {
+        let axis = Vec2::new(1f32, 0f32);
+        return Some(OrientedRect {
+            center: origin,
+            axis,
+            width: 0f32,
+            height: 0f32,
+        });
+    }
+    let (mut right, mut top, mut left) = (1, 1, 1);
+    let mut best: Option<(f64, OrientedRect)> = None;
+    for i in 0..n {
+        let (a, b) = (hull[i], hull[(i + 1) % n]);
+        let length = distance_squared(a, b).sqrt();
+        let u = [(b[0] - a[0]) / length, (b[1] - a[1]) / length];
+        let v = [-u[1], u[0]];
+        // Extreme vertices only move forward, when edge turns counter-clockwise.
+        right = advance(&hull, right, |p| dot(u, p));
+        if i == 0 {
+            top = right;
+        }
+        top = advance(&hull, top, |p| dot(v, p));
+        if i == 0 {
+            left = top;
+        }
+        left = advance(&hull, left, |p| -dot(u, p));
+
+        let (u_min, u_max) = (dot(u, hull[left]), dot(u, hull[right]));
+        let (v_min, v_max) = (dot(v, a), dot(v, hull[top]));
+        let (width, height) = (u_max - u_min, v_max - v_min);
+        let cost = cost(width, height);
+        if best.as_ref().map_or(false, |&(best, _)| best <= cost) {
+            continue;
+        }
+        let (cu, cv) = ((u_min + u_max) * 0.5f64, (v_min + v_max) * 0.5f64);
+        let center = [u[0] * cu + v[0] * cv, u[1] * cu + v[1] * cv];
+        let rect = OrientedRect {
+            center: origin + Vec2::new(center[0] as f32, center[1] as f32),
+            axis: Vec2::new(u[0] as f32, u[1] as f32),
+            width: width as f32,
+            height: height as f32,
+        };
+        best = Some((cost, rect));
+    }
+    best.map(|(_, rect)| rect)
+}
+
+/// The smallest circle through two or three points, squared radius.
+fn circle_through(points: &[Xy]) -> (Xy, f64) {
+    let diameter = |a: Xy, b: Xy| {
+        let center = [(a[0] + b[0]) * 0.5f64, (a[1] + b[1]) * 0.5f64];
+        (center, distance_squared(a, b) * 0.25f64)
+    };
+    let (a, b) = (points[0], points[1]);
+    let c = match points.get(2) {
+        Some(&c) => c,
+        None => return diameter(a, b),
+    };
+    let (bx, by) = (b[0] - a[0], b[1] - a[1]);
+    let (cx, cy) = (c[0] - a[0], c[1] - a[1]);
+    let d = 2f64 * (bx * cy - by * cx);
+    if d == 0f64 {
+        // Collinear points are enclosed by circle on the farthest pair.
+        let pairs = [diameter(a, b), diameter(b, c), diameter(c, a)];
+        return pairs.iter().copied().fold(
+            pairs[0],
+            |max, pair| if pair.1 > max.1 { pair } else { max },
+        );
+    }
+    let b_len = bx * bx + by * by;
+    let c_len = cx * cx + cy * cy;
+    let x = (cy * b_len - by * c_len) / d;
+    let y = (bx * c_len - cx * b_len) / d;
+    ([a[0] + x, a[1] + y], x * x + y * y)
+}
+
+/// Smallest circle around `points` by Welzl's algorithm in iterative form.
+fn welzl(points: &mut [Xy]) -> (Xy, f64) {
+    // Points in random order make expected time linear. Fixed seed keeps result reproducible.
+    let mut state = 0x5eed_u64;
+    for i in (1..points.len()).rev() {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        points.swap(i, (state >> 33) as usize % (i + 1));
+    }
+    let outside =
+        |(center, radius): (Xy, f64), p: Xy| distance_squared(center, p) > radius * (1f64 + 1e-12);
+    let mut circle = (points[0], 0f64);
+    for i in 1..points.len() {
+        if !outside(circle, points[i]) {
+            continue;
+        }
+        circle = (points[i], 0f64);
+        for j in 0..i {
+            if !outside(circle, points[j]) {
+                continue;
+            }
+            circle = circle_through(&[points[i], points[j]]);
+            for k in 0..j {
+                if outside(circle, points[k]) {
+                    circle = circle_through(&[points[i], points[j], points[k]]);
+                }
+            }
+        }
+    }
+    circle
+}
+
+impl<P: Point> Outline<P> {
+    /// Axis-aligned bounding box of vertices. `None` for empty outline.
+    pub fn aabb(&self) -> Option<Aabb<P>> {
+        let (&first, rest) = self.vertices().split_first()?;
+        let (mut min, mut max) = ((first.x(), first.y()), (first.x(), first.y()));
+        for &p in rest {
+            if p.x() < min.0 {
+                min.0 = p.x();
+            } else if p.x() > max.0 {
+                max.0 = p.x();
+            }
+            if p.y() < min.1 {
+                min.1 = p.y();
+            } else if p.y() > max.1 {
+                max.1 = p.y();
+            }
+        }
+        Some(Aabb {
+            min: P::from_xy(min.0, min.1),
+            max: P::from_xy(max.0, max.1),
+        })
+    }
+}
+
+impl Outline {
+    /// Oriented rectangle of the least area around vertices by rotating calipers in
+    /// `O(n log n)` time. `None` for empty outline.
+    pub fn min_area_rect(&self) -> Option<OrientedRect> {
+        calipers(self.vertices(), |width, height| width * height)
+    }
+
+    /// Oriented rectangle of the least perimeter around vertices. See
+    /// [`Outline::min_area_rect`].
+    pub fn min_perimeter_rect(&self) -> Option<OrientedRect> {
+        calipers(self.vertices(), |width, height| width + height)
+    }
+
+    /// The smallest circle around vertices by Welzl's algorithm in expected `O(n)` time.
+    /// Radius is rounded up, so circle contains every vertex. `None` for empty outline.
+    pub fn min_enclosing_circle(&self) -> Option<Circle> {
+        let origin = *self.vertices().first()?;
+        let mut points: Vec<Xy> = self.vertices().iter().map(|&p| xy(p - origin)).collect();
+        let (center, _) = welzl(&mut points);
+        let center = origin + Vec2::new(center[0] as f32, center[1] as f32);
+        // Radius from rounded center.
+        let squared = self
+            .vertices()
+            .iter()
+            .map(|&p| distance_squared(xy(center), xy(p)))
+            .fold(0f64, f64::max);
+        let mut radius = squared.sqrt() as f32;
+        while f64::from(radius).powi(2) < squared {
+            // Next float after non-negative one has the next bit pattern.
+            radius = f32::from_bits(radius.to_bits() + 1);
+        }
+        Some(Circle { center, radius })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aabb, OrientedRect};
+    use crate::geometry::circumcenter;
+    use crate::outline::Outline;
+    use crate::testing::Random;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn aabb() {
+        let triangle = Outline::new(vec![[1i32, 5], [-2, 0], [4, 2]].into_iter());
+        let aabb = triangle.aabb().unwrap();
+        assert_eq!(
+            aabb,
+            Aabb {
+                min: [-2, 0],
+                max: [4, 5]
+            }
+        );
+        assert!(aabb.contains([4, 0]));
+        assert!(!aabb.contains([0, 6]));
+        assert_eq!(
+            Outline::from(aabb).vertices(),
+            &[[-2, 0], [4, 0], [4, 5], [-2, 5]]
+        );
+        assert_eq!(
+            outline(&[(1f32, 2f32), (3f32, 5f32)])
+                .aabb()
+                .unwrap()
+                .area(),
+            6f32
+        );
+        assert_eq!(outline(&[]).aabb(), None);
+    }
+
+    #[test]
+    fn oriented_rects() {
+        // Square rotated by 45 degrees.
+        let diamond = outline(&[(1f32, 0f32), (2f32, 1f32), (1f32, 2f32), (0f32, 1f32)]);
+        let rect = diamond.min_area_rect().unwrap();
+        assert!((rect.area() - 2f32).abs() < 1e-6f32);
+        assert!((rect.center - Vec2::new(1f32, 1f32)).length() < 1e-6f32);
+        assert!((rect.axis.x().abs() - rect.axis.y().abs()).abs() < 1e-6f32);
+        assert_eq!(diamond.aabb().unwrap().area(), 4f32);
+
+        let triangle = outline(&[(0f32, 0f32), (4f32, 0f32), (0f32, 3f32)]);
+        let expected = OrientedRect {
+            center: Vec2::new(2f32, 1.5f32),
+            axis: Vec2::new(1f32, 0f32),
+            width: 4f32,
+            height: 3f32,
+        };
+        assert_eq!(triangle.min_area_rect(), Some(expected));
+        assert_eq!(triangle.min_perimeter_rect(), Some(expected));
+        let corners = Outline::from(expected);
+        assert!(corners.is_ccw());
+        assert_eq!(corners.vertices()[0], Vec2::new(0f32, 0f32));
+
+        let segment = outline(&[(0f32, 0f32), (3f32, 4f32)])
+            .min_area_rect()
+            .unwrap();
+        assert_eq!((segment.width, segment.height), (5f32, 0f32));
+        assert_eq!(outline(&[]).min_perimeter_rect(), None);
+    }
+
+    #[test]
+    fn enclosing_circles() {
+        let square = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        let circle = square.min_enclosing_circle().unwrap();
+        assert_eq!(circle.center, Vec2::new(1f32, 1f32));
+        assert!((circle.radius - 2f32.sqrt()).abs() < 1e-6f32);
+        // Circle on the longest side of obtuse triangle.
+        let obtuse = outline(&[(0f32, 0f32), (4f32, 0f32), (2f32, 1f32)]);
+        let circle = obtuse.min_enclosing_circle().unwrap();
+        assert_eq!(
+            (circle.center, circle.radius),
+            (Vec2::new(2f32, 0f32), 2f32)
+        );
+
+        let polygon = circle.to_outline(8);
+        assert_eq!(polygon.len(), 8);
+        assert!(polygon.is_ccw());
+        for i in 0..100 {
+            let angle = i as f32 * 0.0628f32;
+            let p = circle.center + Vec2::new(angle.cos(), angle.sin()) * circle.radius * 0.999f32;
+            assert!(polygon.contains(p));
+        }
+        let point = outline(&[(3f32, 4f32)]).min_enclosing_circle().unwrap();
+        assert_eq!((point.center, point.radius), (Vec2::new(3f32, 4f32), 0f32));
+    }
+
+    #[test]
+    fn random_against_brute_force() {
+        let mut random = Random(0xb0b);
+        for _ in 0..200 {
+            let len = 1 + random.next(12) as usize;
+            let points: Vec<_> = (0..len)
+                .map(|_| (random.next(20) * 0.7f32, random.next(20) * 0.3f32))
+                .collect();
+            let ring = outline(&points);
+            let vertices = ring.vertices();
+            let inside = |rect: &OrientedRect, p: Vec2| {
+                let across = Vec2::new(-rect.axis.y(), rect.axis.x());
+                let d = p - rect.center;
+                d.dot(rect.axis).abs() <= rect.width * 0.5f32 + 1e-4f32
+                    && d.dot(across).abs() <= rect.height * 0.5f32 + 1e-4f32
+            };
+
+            // Optimal rectangle has side along direction between some pair of vertices.
+            let mut least = (f32::INFINITY, f32::INFINITY);
+            for &a in vertices {
+                for &b in vertices {
+                    if a == b {
+                        continue;
+                    }
+                    let axis = (b - a).normalize();
+                    let across = Vec2::new(-axis.y(), axis.x());
+                    let extent = |dir: Vec2| {
+                        let (min, max) = vertices
+                            .iter()
+                            .fold((f32::INFINITY, -f32::INFINITY), |(min, max), &p| {
+                                (min.min(p.dot(dir)), max.max(p.dot(dir)))
+                            });
+                        max - min
+                    };
+                    let (width, height) = (extent(axis), extent(across));
+                    least.0 = least.0.min(width * height);
+                    least.1 = least.1.min(width + height);
+                }
+            }
+            let area = ring.min_area_rect().unwrap();
+            let perimeter = ring.min_perimeter_rect().unwrap();
+            if least.0.is_finite() {
+                assert!((area.area() - least.0).abs() < 1e-3f32);
+                assert!((perimeter.perimeter() * 0.5f32 - least.1).abs() < 1e-3f32);
+            }
+            let circle = ring.min_enclosing_circle().unwrap();
+            for &p in vertices {
+                assert!(inside(&area, p) && inside(&perimeter, p));
+                assert!(circle.contains(p));
+            }
+
+            // Optimal circle passes through two or three vertices.
+            let mut radius = f32::INFINITY;
+            let encloses = |center: Vec2, r: f32| {
+                vertices
+                    .iter()
+                    .all(|&p| (p - center).length() <= r * (1f32 + 1e-5f32) + 1e-5f32)
+            };
+            for &a in vertices {
+                for &b in vertices {
+                    let center = (a + b) * 0.5f32;
+                    let r = (a - center).length();
+                    if encloses(center, r) {
+                        radius = radius.min(r);
+                    }
+                    for &c in vertices {
+                        let cross = (b - a).x() * (c - a).y() - (b - a).y() * (c - a).x();
+                        if cross == 0f32 {
+                            continue;
+                        }
+                        let center = circumcenter(a, b, c);
+                        let r = (a - center).length();
+                        if encloses(center, r) {
+                            radius = radius.min(r);
+                        }
+                    }
+                }
+            }
+            assert!((circle.radius - radius).abs() < 1e-3f32);
+        }
+    }
+}
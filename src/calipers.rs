@@ -0,0 +1,385 @@
+//! Measurements of convex outlines by rotating calipers: diameter, width, antipodal pairs and
+//! the maximum distance between two outlines.
+//!
+//! Two parallel lines rotate around outline touching it from opposite sides, so every query
+//! takes `O(n)` time. Order of edge directions is decided exactly.
+use crate::geometry::{cross64, total_cmp};
+use crate::outline::Outline;
+use glam::Vec2;
+use std::cmp::Ordering;
+
+type Xy = [f64; 2];
+
+fn xy(p: Vec2) -> Xy {
+    [f64::from(p.x()), f64::from(p.y())]
+}
+
+fn distance(a: Xy, b: Xy) -> f64 {
+    (b[0] - a[0]).hypot(b[1] - a[1])
+}
+
+/// Pair of vertices with distance between them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexPair {
+    /// Index of vertex of the first outline.
+    pub first: usize,
+    /// Index of vertex of the second outline, which is the same for diameter.
+    pub second: usize,
+    /// Distance between vertices.
+    pub distance: f32,
+}
+
+/// The least distance between parallel lines enclosing outline. One of lines goes along
+/// edge, another one goes through opposite vertex.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Width {
+    /// Distance between lines.
+    pub width: f32,
+    /// Unit normal of lines pointing from edge to vertex.
+    pub direction: Vec2,
+    /// Index of edge on the first line.
+    pub edge: usize,
+    /// Index of vertex on the second line.
+    pub vertex: usize,
+}
+
+/// Vertices of convex outline in counter-clockwise order without repeats, with their indices.
+fn ccw(outline: &Outline) -> (Vec<Xy>, Vec<usize>) {
+    let mut indices: Vec<usize> = (0..outline.len()).collect();
+    if !outline.is_ccw() {
+        indices.reverse();
+    }
+    let v = outline.vertices();
+    indices.dedup_by(|&mut i, &mut j| v[i] == v[j]);
+    while indices.len() > 1 && v[indices[0]] == v[*indices.last().unwrap()] {
+        indices.pop();
+    }
+    (indices.iter().map(|&i| xy(v[i])).collect(), indices)
+}
+
+/// Index of the lowest vertex, the leftmost of such.
+fn lowest(points: &[Xy]) -> usize {
+    (0..points.len())
+        .min_by(|&i, &j| {
+            let (p, q) = (points[i], points[j]);
+            total_cmp(p[1], q[1]).then(total_cmp(p[0], q[0]))
+        })
+        .unwrap_or(0)
+}
+
+/// Order of directions of edges from `a` to `b` and from `c` to `d` by angle from positive x
+/// axis in range `[0, 2π)`.
+fn cmp_direction(a: Xy, b: Xy, c: Xy, d: Xy) -> Ordering {
+    let lower = |from: Xy, to: Xy| to[1] < from[1] || (to[1] == from[1] && to[0] < from[0]);
+    lower(a, b)
+        .cmp(&lower(c, d))
+        .then_with(|| total_cmp(0f64, cross64(a, b, c, d) + 0f64))
+}
+
+/// Pair of vertex of `p` and vertex of `q` extreme in opposite directions.
+#[derive(Debug, Clone, Copy)]
+struct Opposite {
+    p: usize,
+    q: usize,
+    /// Calipers turn along edge of `p` starting at vertex `p`.
+    along_p: bool,
+}
+
+/// Pairs of vertices of convex counter-clockwise `p` and `q`, which have parallel supporting
+/// lines with `p` and `q` at opposite sides. Calipers turn as edges of Minkowski sum of `p`
+/// and reflected `q` go around.
+fn opposite(p: &[Xy], q: &[Xy]) -> Vec<Opposite> {
+    let reflected: Vec<Xy> = q.iter().map(|&[x, y]| [-x, -y]).collect();
+    let (n, m) = (p.len(), q.len());
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+    let (p_start, q_start) = (lowest(p), lowest(&reflected));
+    let edges = |len: usize| if len > 1 { len } else { 0 };
+    let (mut i, mut j) = (0, 0);
+    let mut pairs = Vec::new();
+    loop {
+        let (a, b) = ((p_start + i) % n, (q_start + j) % m);
+        let order = match (i < edges(n), j < edges(m)) {
+            (false, false) => {
+                if pairs.is_empty() {
+                    pairs.push(Opposite {
+                        p: a,
+                        q: b,
+                        along_p: false,
+                    });
+                }
+                return pairs;
+            }
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (true, true) => {
+                cmp_direction(p[a], p[(a + 1) % n], reflected[b], reflected[(b + 1) % m])
+            }
+        };
+        pairs.push(Opposite {
+            p: a,
+            q: b,
+            along_p: order != Ordering::Greater,
+        });
+        match order {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                // Parallel edges, so every vertex of one is opposite to every vertex of other.
+                let (next_a, next_b) = ((a + 1) % n, (b + 1) % m);
+                pairs.push(Opposite {
+                    p: next_a,
+                    q: b,
+                    along_p: false,
+                });
+                pairs.push(Opposite {
+                    p: a,
+                    q: next_b,
+                    along_p: false,
+                });
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+/// The farthest of opposite pairs.
+fn farthest(pairs: &[Opposite], p: &[Xy], q: &[Xy]) -> Option<(Opposite, f64)> {
+    pairs
+        .iter()
+        .map(|&pair| (pair, distance(p[pair.p], q[pair.q])))
+        .fold(None, |best, (pair, d)| match best {
+            Some((_, best_d)) if best_d >= d => best,
+            _ => Some((pair, d)),
+        })
+}
+
+impl Outline {
+    /// Pairs of vertices, through which pass parallel lines with outline between them. Every
+    /// pair is reported once as `(i, j)` with `i < j`, pairs are sorted. Outline **MUST** be
+    /// convex, but may have any orientation.
+    pub fn antipodal_pairs(&self) -> Vec<(usize, usize)> {
+        let (points, indices) = ccw(self);
+        let mut pairs: Vec<(usize, usize)> = opposite(&points, &points)
+            .into_iter()
+            .filter(|pair| pair.p != pair.q)
+            .map(|pair| {
+                let (i, j) = (indices[pair.p], indices[pair.q]);
+                (i.min(j), i.max(j))
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    /// The farthest pair of vertices. `None` for empty outline. Outline **MUST** be convex,
+    /// see [`Outline::antipodal_pairs`].
+    pub fn diameter(&self) -> Option<VertexPair> {
+        let (points, indices) = ccw(self);
+        let pairs = opposite(&points, &points);
+        let (pair, distance) = farthest(&pairs, &points, &points)?;
+        let (i, j) = (indices[pair.p], indices[pair.q]);
+        Some(VertexPair {
+            first: i.min(j),
+            second: i.max(j),
+            distance: distance as f32,
+        })
+    }
+
+    /// The least width of outline with its direction. `None` for outline with less than two
+    /// distinct vertices. Outline **MUST** be convex, see [`Outline::antipodal_pairs`].
+    pub fn min_width(&self) -> Option<Width> {
+        let (points, indices) = ccw(self);
+        let n = points.len();
+        let mut best: Option<(f64, usize, usize)> = None;
+        for pair in opposite(&points, &points) {
+            if !pair.along_p || n < 2 {
+                continue;
+            }
+            let (a, b) = (points[pair.p], points[(pair.p + 1) % n]);
+            let width = cross64(a, b, a, points[pair.q]) / distance(a, b);
+            if best.map_or(true, |(least, _, _)| width < least) {
+                best = Some((width, pair.p, pair.q));
+            }
+        }
+        let (width, edge, vertex) = best?;
+        let (a, b) = (points[edge], points[(edge + 1) % n]);
+        let length = distance(a, b);
+        let direction = [(a[1] - b[1]) / length, (b[0] - a[0]) / length];
+        // Edge goes backward in original order for clockwise outline.
+        let edge = if self.is_ccw() {
+            indices[edge]
+        } else {
+            indices[(edge + 1) % n]
+        };
+        Some(Width {
+            width: width as f32,
+            direction: Vec2::new(direction[0] as f32, direction[1] as f32),
+            edge,
+            vertex: indices[vertex],
+        })
+    }
+
+    /// The farthest pair of vertex of this outline and vertex of `other`. `None` if any
+    /// outline is empty. Both outlines **MUST** be convex, see [`Outline::antipodal_pairs`].
+    pub fn max_distance(&self, other: &Outline) -> Option<VertexPair> {
+        let (p, p_indices) = ccw(self);
+        let (q, q_indices) = ccw(other);
+        if p.is_empty() || q.is_empty() {
+            return None;
+        }
+        let (pair, distance) = farthest(&opposite(&p, &q), &p, &q)?;
+        Some(VertexPair {
+            first: p_indices[pair.p],
+            second: q_indices[pair.q],
+            distance: distance as f32,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VertexPair, Width};
+    use crate::hull::convex_hull;
+    use crate::outline::Outline;
+    use crate::testing::Random;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn rectangle() {
+        let rect = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 1f32), (0f32, 1f32)]);
+        let diameter = rect.diameter().unwrap();
+        assert_eq!((diameter.first, diameter.second), (0, 2));
+        assert_eq!(diameter.distance, 5f32.sqrt());
+        assert_eq!(
+            rect.min_width(),
+            Some(Width {
+                width: 1f32,
+                direction: Vec2::new(0f32, 1f32),
+                edge: 0,
+                vertex: 2,
+            })
+        );
+        let all = vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+        assert_eq!(rect.antipodal_pairs(), all);
+
+        // Clockwise outline gives indices of its own vertices.
+        let mut clockwise = rect.clone();
+        clockwise.reverse();
+        let width = clockwise.min_width().unwrap();
+        assert_eq!(width.width, 1f32);
+        let (a, b) = clockwise.edge(width.edge as isize);
+        assert_eq!(a.y(), b.y());
+        assert_ne!(clockwise.vertices()[width.vertex].y(), a.y());
+        assert_eq!(clockwise.antipodal_pairs(), all);
+    }
+
+    #[test]
+    fn triangle_and_degenerate() {
+        let triangle = outline(&[(0f32, 0f32), (4f32, 0f32), (0f32, 3f32)]);
+        assert_eq!(triangle.antipodal_pairs(), vec![(0, 1), (0, 2), (1, 2)]);
+        let width = triangle.min_width().unwrap();
+        assert_eq!((width.edge, width.vertex), (1, 0));
+        assert!((width.width - 2.4f32).abs() < 1e-6f32);
+
+        let segment = outline(&[(0f32, 0f32), (3f32, 4f32)]);
+        assert_eq!(segment.diameter().unwrap().distance, 5f32);
+        assert_eq!(segment.min_width().unwrap().width, 0f32);
+        let point = outline(&[(1f32, 1f32), (1f32, 1f32)]);
+        assert_eq!(point.diameter().unwrap().distance, 0f32);
+        assert_eq!(point.min_width(), None);
+        assert_eq!(outline(&[]).diameter(), None);
+    }
+
+    #[test]
+    fn two_outlines() {
+        let left = outline(&[(0f32, 0f32), (1f32, 0f32), (1f32, 1f32), (0f32, 1f32)]);
+        let right = outline(&[(5f32, 0f32), (7f32, 1f32), (5f32, 2f32)]);
+        assert_eq!(
+            left.max_distance(&right),
+            Some(VertexPair {
+                first: 0,
+                second: 1,
+                distance: 50f32.sqrt(),
+            })
+        );
+        assert_eq!(left.max_distance(&outline(&[])), None);
+    }
+
+    fn random_convex(random: &mut Random) -> Outline {
+        let len = 1 + random.next(20) as usize;
+        let offset = Vec2::new(random.next(30), random.next(30));
+        let mut hull = convex_hull(
+            (0..len)
+                .map(|_| offset + Vec2::new(random.next(16) * 0.7f32, random.next(16) * 0.3f32)),
+        );
+        if random.next(1) == 0f32 {
+            hull.reverse();
+        }
+        hull
+    }
+
+    #[test]
+    fn random_against_brute_force() {
+        let mut random = Random(0xca1);
+        for _ in 0..300 {
+            let (p, q) = (random_convex(&mut random), random_convex(&mut random));
+            let v = p.vertices();
+            let pairs = |a: &[Vec2], b: &[Vec2]| {
+                let mut pairs = Vec::new();
+                for (i, &x) in a.iter().enumerate() {
+                    for (j, &y) in b.iter().enumerate() {
+                        pairs.push((i, j, (x - y).length()));
+                    }
+                }
+                pairs
+            };
+            let max = |pairs: Vec<(usize, usize, f32)>| {
+                pairs.into_iter().map(|(_, _, d)| d).fold(0f32, f32::max)
+            };
+            let close = |a: f32, b: f32| (a - b).abs() < 1e-4f32;
+            assert!(close(p.diameter().unwrap().distance, max(pairs(v, v))));
+            let farthest = p.max_distance(&q).unwrap();
+            assert!(close(farthest.distance, max(pairs(v, q.vertices()))));
+            let (a, b) = (v[farthest.first], q.vertices()[farthest.second]);
+            assert!(close((a - b).length(), farthest.distance));
+
+            let diameter = p.diameter().unwrap();
+            let antipodal = p.antipodal_pairs();
+            if v.len() >= 2 {
+                assert!(antipodal.contains(&(diameter.first, diameter.second)));
+            }
+            assert!(antipodal.len() <= 3 * v.len() / 2);
+
+            if v.len() < 3 {
+                continue;
+            }
+            // Width is the least over edges of the farthest distance from edge line.
+            let least = (0..v.len() as isize)
+                .map(|i| {
+                    let (a, b) = p.edge(i);
+                    let normal = Vec2::new(a.y() - b.y(), b.x() - a.x()).normalize();
+                    v.iter()
+                        .map(|&c| (c - a).dot(normal).abs())
+                        .fold(0f32, f32::max)
+                })
+                .fold(f32::INFINITY, f32::min);
+            let width = p.min_width().unwrap();
+            assert!((width.width - least).abs() < 1e-4f32);
+            let (a, _) = p.edge(width.edge as isize);
+            let across = (v[width.vertex] - a).dot(width.direction);
+            assert!((across - width.width).abs() < 1e-4f32);
+            for &c in v {
+                let d = (c - a).dot(width.direction);
+                assert!(d > -1e-4f32 && d < width.width + 1e-4f32);
+            }
+        }
+    }
+}
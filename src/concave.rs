@@ -0,0 +1,224 @@
+//! Concave hulls of unordered point sets, which follow shape of points closer than convex hull.
+//!
+//! Both algorithms take parameter of concavity: small values give tight hulls, large ones
+//! approach [`convex_hull`].
+use crate::delaunay::point_delaunay;
+use crate::geometry::{cmp_length, cmp_turn, total_cmp};
+use crate::hull::convex_hull;
+use crate::location::{FillRule, Location};
+use crate::outline::Outline;
+use crate::overlay::{assemble_rings, group_rings};
+use crate::polygon::Polygon;
+use crate::validation::intersection;
+use glam::Vec2;
+use std::collections::HashSet;
+
+fn xy(p: Vec2) -> [f64; 2] {
+    [f64::from(p.x()), f64::from(p.y())]
+}
+
+/// Radius of circle through vertices of triangle. Infinite for degenerate triangle.
+fn circumradius(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+    let (a, b, c) = (xy(a), xy(b), xy(c));
+    let length = |p: [f64; 2], q: [f64; 2]| (q[0] - p[0]).hypot(q[1] - p[1]);
+    let doubled_area = ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs();
+    length(a, b) * length(b, c) * length(c, a) / (2f64 * doubled_area)
+}
+
+/// Alpha shape of `points`: union of Delaunay triangles with circumradius not greater than
+/// `alpha`. Result has polygon for every connected part of union, with holes where points
+/// are sparse. Takes `O(n log n)` expected time. Points **MUST** be finite.
+/// # Arguments
+/// * `alpha` - concavity parameter. Empty result for small values, convex hull for infinity;
+pub fn alpha_shape(points: &[Vec2], alpha: f32) -> Vec<Polygon> {
+    let alpha = f64::from(alpha);
+    let kept: Vec<[usize; 3]> = point_delaunay(points)
+        .into_iter()
+        .filter(|&[a, b, c]| circumradius(points[a], points[b], points[c]) <= alpha)
+        .collect();
+    let edges = |&[a, b, c]: &[usize; 3]| [(a, b), (b, c), (c, a)];
+    let inner: HashSet<(usize, usize)> = kept.iter().flat_map(edges).collect();
+    // Edge of kept triangle is on boundary, if triangle on its other side isn't kept.
+    let boundary: Vec<(Vec2, Vec2)> = kept
+        .iter()
+        .flat_map(edges)
+        .filter(|&(a, b)| !inner.contains(&(b, a)))
+        .map(|(a, b)| (points[a], points[b]))
+        .collect();
+    group_rings(assemble_rings(&boundary))
+}
+
+/// Indices of at most `k` of `candidates` nearest to `point`.
+fn nearest(points: &[Vec2], candidates: &[usize], point: Vec2, k: usize) -> Vec<usize> {
+    let mut nearest = candidates.to_vec();
+    let distance = |i: usize| f64::from((points[i] - point).length_squared());
+    if nearest.len() > k {
+        // Ties are broken by index, so result doesn't depend on selection algorithm.
+        nearest.select_nth_unstable_by(k, |&i, &j| {
+            total_cmp(distance(i), distance(j)).then(i.cmp(&j))
+        });
+        nearest.truncate(k);
+    }
+    nearest
+}
+
+/// Walks around distinct `points` from the lowest one, going to the rightmost turn among `k`
+/// nearest points, which keeps outline simple. `None`, if walk gets stuck.
+fn walk(points: &[Vec2], k: usize) -> Option<Outline> {
+    let lowest = |&i: &usize, &j: &usize| {
+        let (p, q) = (points[i], points[j]);
+        (p.y(), p.x()).partial_cmp(&(q.y(), q.x())).unwrap()
+    };
+    let first = (0..points.len()).min_by(lowest)?;
+    let mut remaining: Vec<usize> = (0..points.len()).filter(|&i| i != first).collect();
+    let mut hull = vec![first];
+    loop {
+        let current = *hull.last().unwrap();
+        // Early return to the first point would leave out almost everything.
+        if hull.len() == 4 {
+            remaining.push(first);
+        }
+        let from = points[current];
+        // Turns are measured counter-clockwise from the reversed direction of walk, which
+        // starts to the right from the lowest point.
+        let back = match hull.len() {
+            1 => Vec2::new(from.x() - from.x().abs() - 1f32, from.y()),
+            len => points[hull[len - 2]],
+        };
+        let mut candidates = nearest(points, &remaining, from, k);
+        candidates.sort_by(|&i, &j| {
+            let (p, q) = (points[i], points[j]);
+            cmp_turn(from, back, p, q, false).then(cmp_length(from, p, from, q))
+        });
+        let last = hull.len() - 1;
+        let next = candidates.into_iter().find(|&c| {
+            (0..last).all(|j| {
+                let (a, b) = (points[hull[j]], points[hull[j + 1]]);
+                // Adjacent edges may share only their common vertex.
+                let adjacent = j + 1 == last || (c == first && j == 0);
+                match intersection(a, b, from, points[c]) {
+                    Some((_, touch)) => adjacent && touch,
+                    None => true,
+                }
+            })
+        })?;
+        if next == first {
+            return Some(Outline::new(hull.into_iter().map(|i| points[i])));
+        }
+        remaining.retain(|&i| i != next);
+        hull.push(next);
+    }
+}
+
+/// Concave hull of `points` by k-nearest neighbors algorithm of Moreira and Santos. Hull is
+/// counter-clockwise simple outline with every point inside or on boundary. If walk around
+/// points fails, it is repeated with greater `k`, and convex hull is the last resort. Takes
+/// `O(k n²)` time for every attempt. Points **MUST** be finite.
+/// # Arguments
+/// * `k` - concavity parameter, number of neighbors considered at every step. At least 3,
+///   gives convex hull if not less than number of points;
+pub fn concave_hull(points: &[Vec2], k: usize) -> Outline {
+    let mut distinct = points.to_vec();
+    distinct.sort_by(|p, q| (p.x(), p.y()).partial_cmp(&(q.x(), q.y())).unwrap());
+    distinct.dedup();
+    for k in k.max(3)..distinct.len() {
+        if let Some(hull) = walk(&distinct, k) {
+            let locations = hull.locate_all(&distinct, FillRule::NonZero, 0f32);
+            if !locations.contains(&Location::Outside) {
+                return hull;
+            }
+        }
+    }
+    convex_hull(distinct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alpha_shape, concave_hull};
+    use crate::hull::convex_hull;
+    use crate::location::{FillRule, Location};
+    use crate::testing::Random;
+    use glam::Vec2;
+
+    fn grid(width: u32, height: u32, keep: impl Fn(u32, u32) -> bool) -> Vec<Vec2> {
+        (0..=width)
+            .flat_map(|x| (0..=height).map(move |y| (x, y)))
+            .filter(|&(x, y)| keep(x, y))
+            .map(|(x, y)| Vec2::new(x as f32, y as f32))
+            .collect()
+    }
+
+    #[test]
+    fn alpha_shape_with_hole() {
+        let points = grid(4, 4, |x, y| (x, y) != (2, 2));
+        let shape = alpha_shape(&points, 0.8f32);
+        assert_eq!(shape.len(), 1);
+        assert_eq!(shape[0].holes().len(), 1);
+        // Hole is square with corners at neighbors of missing point.
+        assert_eq!(shape[0].area(), 14f32);
+        assert_eq!(shape[0].holes()[0].len(), 4);
+        assert!(!shape[0].contains(Vec2::new(2f32, 2f32)));
+
+        let convex = alpha_shape(&points, f32::INFINITY);
+        assert_eq!(convex.len(), 1);
+        assert_eq!(convex[0].outline().len(), 4);
+        assert_eq!(convex[0].area(), 16f32);
+        assert!(alpha_shape(&points, 0.5f32).is_empty());
+    }
+
+    #[test]
+    fn alpha_shape_parts() {
+        let mut points = grid(2, 2, |_, _| true);
+        points.extend(points.clone().iter().map(|&p| p + Vec2::new(10f32, 0f32)));
+        let shape = alpha_shape(&points, 1f32);
+        assert_eq!(shape.len(), 2);
+        for polygon in &shape {
+            assert_eq!(polygon.area(), 4f32);
+            assert!(polygon.holes().is_empty());
+        }
+        assert!(alpha_shape(&points[..3], 1f32).is_empty());
+    }
+
+    #[test]
+    fn concave_l_shape() {
+        let points = grid(6, 6, |x, y| x <= 2 || y <= 2);
+        let hull = concave_hull(&points, 3);
+        assert!(hull.is_ccw());
+        assert!(hull.validate().is_valid());
+        assert_eq!(hull.signed_area(), 20f32);
+        for location in hull.locate_all(&points, FillRule::NonZero, 0f32) {
+            assert_ne!(location, Location::Outside);
+        }
+        // There are no more neighbors to consider.
+        assert_eq!(
+            concave_hull(&points, 100),
+            convex_hull(points.iter().copied())
+        );
+    }
+
+    #[test]
+    fn random_points() {
+        let mut random = Random(0xc0ca7e);
+        for round in 0..50 {
+            let len = 4 + random.next(60) as usize;
+            let points: Vec<_> = (0..len)
+                .map(|_| Vec2::new(random.next(40), random.next(40)))
+                .collect();
+            let hull = concave_hull(&points, 3 + round % 5);
+            let convex = convex_hull(points.iter().copied());
+            if convex.len() < 3 {
+                continue;
+            }
+            assert!(hull.is_ccw());
+            assert!(hull.validate().is_valid());
+            assert!(hull.signed_area() <= convex.signed_area());
+            for location in hull.locate_all(&points, FillRule::NonZero, 0f32) {
+                assert_ne!(location, Location::Outside);
+            }
+
+            let shape = alpha_shape(&points, 8f32);
+            let area: f32 = shape.iter().map(|polygon| polygon.area()).sum();
+            assert!(area <= convex.signed_area() + 1e-3f32);
+        }
+    }
+}
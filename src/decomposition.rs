@@ -0,0 +1,436 @@
+use crate::outline::Outline;
+use crate::point::Point;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Algorithm of convex decomposition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decomposition {
+    /// Hertel-Mehlhorn: removes unnecessary diagonals of triangulation. Fast, produces at most
+    /// four times more pieces than optimal.
+    HertelMehlhorn,
+    /// Dynamic programming over diagonals with reflex endpoint, in spirit of Keil. Produces
+    /// minimal number of pieces without additional vertices, but takes `O(n^5)` time in worst
+    /// case, so suits only small outlines.
+    Minimal,
+}
+
+impl<P: Point> Outline<P> {
+    /// Decomposes outline into convex counter-clockwise outlines.
+    /// # Arguments
+    /// * `decomposition` - algorithm to use;
+    pub fn convex_decomposition(&self, decomposition: Decomposition) -> Vec<Self> {
+        let pieces = match decomposition {
+            Decomposition::HertelMehlhorn => self.hertel_mehlhorn(),
+            Decomposition::Minimal => MinimalDecomposition::new(self).run(),
+        };
+        pieces
+            .into_iter()
+            .map(|piece| Outline::new(piece.into_iter().map(|i| self[i as isize])))
+            .collect()
+    }
+
+    fn hertel_mehlhorn(&self) -> Vec<Vec<usize>> {
+        let mut pieces: Vec<Option<Vec<usize>>> = Vec::new();
+        let mut owners = HashMap::new();
+        let triangles = self.triangulate();
+        for triangle in &triangles {
+            for i in 0..3 {
+                owners.insert((triangle[i], triangle[(i + 1) % 3]), pieces.len());
+            }
+            pieces.push(Some(triangle.to_vec()));
+        }
+
+        // Diagonals in order of triangulation, so result doesn't depend on hashing.
+        let diagonals: Vec<(usize, usize)> = triangles
+            .iter()
+            .flat_map(|triangle| (0..3).map(move |i| (triangle[i], triangle[(i + 1) % 3])))
+            .filter(|&(a, b)| a < b && owners.contains_key(&(b, a)))
+            .collect();
+        for (a, b) in diagonals {
+            let (p, q) = (owners[&(a, b)], owners[&(b, a)]);
+            let merged = {
+                let (first, second) = match (&pieces[p], &pieces[q]) {
+                    (Some(first), Some(second)) => (first, second),
+                    _ => continue,
+                };
+                // `first` from `b` to `a`, then `second` without `a` and `b`.
+                let mut merged = rotated(first, b);
+                let tail = rotated(second, a);
+                merged.extend_from_slice(&tail[1..tail.len() - 1]);
+                merged
+            };
+            let len = merged.len();
+            let a_pos = merged.iter().position(|&v| v == a).unwrap();
+            // Angle can become reflex only at reflex vertex of outline.
+            let keeps_convex = |pos: usize| {
+                let vertex = merged[pos];
+                self.convex(vertex as isize)
+                    || turns_left(
+                        self[merged[(pos + len - 1) % len] as isize],
+                        self[vertex as isize],
+                        self[merged[(pos + 1) % len] as isize],
+                    )
+            };
+            if keeps_convex(0) && keeps_convex(a_pos) {
+                for i in 0..len {
+                    owners.insert((merged[i], merged[(i + 1) % len]), p);
+                }
+                owners.remove(&(a, b));
+                owners.remove(&(b, a));
+                pieces[p] = Some(merged);
+                pieces[q] = None;
+            }
+        }
+        pieces.into_iter().flatten().collect()
+    }
+}
+
+/// Ring rotated to start from `start` vertex.
+fn rotated(ring: &[usize], start: usize) -> Vec<usize> {
+    let pos = ring.iter().position(|&v| v == start).unwrap();
+    ring[pos..]
+        .iter()
+        .chain(ring[..pos].iter())
+        .copied()
+        .collect()
+}
+
+/// Test if path `a`, `b`, `c` turns left at `b` or goes straight.
+fn turns_left<P: Point>(a: P, b: P, c: P) -> bool {
+    let between = |a, b, c| (a < b && b < c) || (a > b && b > c);
+    match P::orient(a, b, c) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => between(a.x(), b.x(), c.x()) || between(a.y(), b.y(), c.y()),
+    }
+}
+
+/// Test if segments `ab` and `cd` have common point.
+fn segments_touch<P: Point>(a: P, b: P, c: P, d: P) -> bool {
+    let within = |p, q, r| (p <= r && r <= q) || (q <= r && r <= p);
+    let on_segment = |p: P, q: P, r: P| within(p.x(), q.x(), r.x()) && within(p.y(), q.y(), r.y());
+    let (d1, d2) = (P::orient(a, b, c), P::orient(a, b, d));
+    let (d3, d4) = (P::orient(c, d, a), P::orient(c, d, b));
+    let opposite = |x: Ordering, y: Ordering| x != Ordering::Equal && x == y.reverse();
+    if opposite(d1, d2) && opposite(d3, d4) {
+        return true;
+    }
+    (d1 == Ordering::Equal && on_segment(a, b, c))
+        || (d2 == Ordering::Equal && on_segment(a, b, d))
+        || (d3 == Ordering::Equal && on_segment(c, d, a))
+        || (d4 == Ordering::Equal && on_segment(c, d, b))
+}
+
+const INFINITE: u32 = u32::MAX;
+
+/// Minimal convex decomposition. `best[i][j]` is number of pieces of sub-outline from `i` to `j`
+/// closed by diagonal `(j, i)`.
+struct MinimalDecomposition<'a, P> {
+    outline: &'a Outline<P>,
+    len: usize,
+    reflex: Vec<bool>,
+    valid: Vec<bool>,
+    best: Vec<u32>,
+    /// Convex piece adjacent to diagonal in optimal decomposition of sub-outline.
+    choice: Vec<Vec<usize>>,
+}
+
+impl<'a, P: Point> MinimalDecomposition<'a, P> {
+    fn new(outline: &'a Outline<P>) -> Self {
+        let len = outline.len();
+        let reflex = (0..len).map(|i| outline.concave(i as isize)).collect();
+        let mut decomposition = MinimalDecomposition {
+            outline,
+            len,
+            reflex,
+            valid: vec![false; len * len],
+            best: vec![INFINITE; len * len],
+            choice: vec![Vec::new(); len * len],
+        };
+        for i in 0..len {
+            for j in i + 1..len {
+                let valid = decomposition.is_valid(i, j);
+                decomposition.valid[i * len + j] = valid;
+            }
+        }
+        decomposition
+    }
+
+    fn point(&self, i: usize) -> P {
+        self.outline[i as isize]
+    }
+
+    /// Test if `i < j` are connected by edge or by diagonal, which lies inside of outline.
+    fn is_valid(&self, i: usize, j: usize) -> bool {
+        let len = self.len;
+        if j == i + 1 || (i == 0 && j == len - 1) {
+            return true;
+        }
+        if !self.reflex[i] && !self.reflex[j] {
+            // Diagonal between two convex vertices is never needed in minimal decomposition.
+            return false;
+        }
+        let (a, b) = (self.point(i), self.point(j));
+        let inside_at = |v: usize, target: P| {
+            let (prev, that, next) = self.outline.prev_that_next(v as isize);
+            if P::orient(prev, that, next) != Ordering::Less {
+                P::orient(that, next, target) == Ordering::Greater
+                    && P::orient(that, target, prev) == Ordering::Greater
+            } else {
+                P::orient(that, target, next) == Ordering::Less
+                    || P::orient(that, prev, target) == Ordering::Less
+            }
+        };
+        if !inside_at(i, b) || !inside_at(j, a) {
+            return false;
+        }
+        (0..len).all(|k| {
+            let next = (k + 1) % len;
+            if k == i || k == j || next == i || next == j {
+                return true;
+            }
+            !segments_touch(a, b, self.point(k), self.point(next))
+        })
+    }
+
+    fn valid(&self, i: usize, j: usize) -> bool {
+        self.valid[i * self.len + j]
+    }
+
+    fn run(mut self) -> Vec<Vec<usize>> {
+        let len = self.len;
+        if len < 3 {
+            return Vec::new();
+        }
+        for i in 0..len - 1 {
+            self.best[i * len + i + 1] = 0;
+        }
+        let mut cost = vec![INFINITE; len * len];
+        let mut from = vec![0usize; len * len];
+        for i in (0..len).rev() {
+            for a1 in i + 1..len {
+                let initial = self.best[i * len + a1];
+                if !self.valid(i, a1) || initial == INFINITE {
+                    continue;
+                }
+                self.grow_chains(i, a1, &mut cost, &mut from);
+            }
+        }
+        let mut pieces = Vec::new();
+        self.collect(0, len - 1, &mut pieces);
+        pieces
+    }
+
+    /// Finds the cheapest convex pieces of sub-outlines `(i, j)`, which start with edge
+    /// `(i, a1)`.
+    fn grow_chains(&mut self, i: usize, a1: usize, cost: &mut [u32], from: &mut [usize]) {
+        let len = self.len;
+        for p in i..len {
+            for c in a1..len {
+                cost[p * len + c] = INFINITE;
+            }
+        }
+        cost[i * len + a1] = self.best[i * len + a1];
+        for c in a1..len {
+            let starts = std::iter::once(i).chain(a1..c);
+            for p in starts {
+                let current = cost[p * len + c];
+                if current == INFINITE {
+                    continue;
+                }
+                for d in c + 1..len {
+                    if !self.valid(c, d) || !turns_left(self.point(p), self.point(c), self.point(d))
+                    {
+                        continue;
+                    }
+                    let sub = self.best[c * len + d];
+                    if sub == INFINITE {
+                        continue;
+                    }
+                    if current + sub < cost[c * len + d] {
+                        cost[c * len + d] = current + sub;
+                        from[c * len + d] = p;
+                    }
+                }
+            }
+        }
+
+        let (pi, pa1) = (self.point(i), self.point(a1));
+        for j in a1 + 1..len {
+            let pj = self.point(j);
+            if !self.valid(i, j)
+                || P::orient(pi, pa1, pj) != Ordering::Greater
+                || !turns_left(pj, pi, pa1)
+            {
+                continue;
+            }
+            for p in a1..j {
+                let current = cost[p * len + j];
+                if current == INFINITE || !turns_left(self.point(p), pj, pi) {
+                    continue;
+                }
+                if current + 1 < self.best[i * len + j] {
+                    self.best[i * len + j] = current + 1;
+                    let mut chain = vec![j];
+                    let (mut p, mut c) = (p, j);
+                    while c != a1 {
+                        chain.push(p);
+                        let prev = from[p * len + c];
+                        c = p;
+                        p = prev;
+                    }
+                    chain.push(i);
+                    chain.reverse();
+                    self.choice[i * len + j] = chain;
+                }
+            }
+        }
+    }
+
+    fn collect(&self, i: usize, j: usize, pieces: &mut Vec<Vec<usize>>) {
+        let chain = &self.choice[i * self.len + j];
+        if chain.is_empty() {
+            return;
+        }
+        for pair in chain.windows(2) {
+            if pair[1] > pair[0] + 1 {
+                self.collect(pair[0], pair[1], pieces);
+            }
+        }
+        pieces.push(chain.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decomposition;
+    use crate::geometry::orient;
+    use crate::outline::Outline;
+    use crate::point::Point;
+    use glam::Vec2;
+    use std::cmp::Ordering;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    fn assert_convex_cover(outline: &Outline, pieces: &[Outline]) {
+        let mut area = 0f32;
+        for piece in pieces {
+            for i in 0..piece.len() as isize {
+                let (prev, that, next) = piece.prev_that_next(i);
+                assert!(orient(prev, that, next) >= 0f64);
+            }
+            assert!(piece.signed_area() > 0f32);
+            area += piece.signed_area();
+        }
+        assert!((area - outline.signed_area()).abs() < 1e-4f32);
+    }
+
+    fn l_shape() -> Outline {
+        outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 1f32),
+            (1f32, 1f32),
+            (1f32, 2f32),
+            (0f32, 2f32),
+        ])
+    }
+
+    fn u_shape() -> Outline {
+        outline(&[
+            (0f32, 0f32),
+            (3f32, 0f32),
+            (3f32, 3f32),
+            (2f32, 3f32),
+            (2f32, 1f32),
+            (1f32, 1f32),
+            (1f32, 3f32),
+            (0f32, 3f32),
+        ])
+    }
+
+    fn star(tips: usize) -> Outline {
+        Outline::new((0..2 * tips).map(|i| {
+            let angle = i as f32 * std::f32::consts::PI / tips as f32;
+            let radius = if i % 2 == 0 { 2f32 } else { 1f32 };
+            Vec2::new(angle.cos(), angle.sin()) * radius
+        }))
+    }
+
+    #[test]
+    fn convex_outline_is_kept() {
+        let square = outline(&[(0f32, 0f32), (1f32, 0f32), (1f32, 1f32), (0f32, 1f32)]);
+        for &mode in &[Decomposition::HertelMehlhorn, Decomposition::Minimal] {
+            let pieces = square.convex_decomposition(mode);
+            assert_eq!(pieces.len(), 1);
+            assert_convex_cover(&square, &pieces);
+        }
+    }
+
+    #[test]
+    fn hertel_mehlhorn() {
+        for (outline, optimal) in &[(l_shape(), 2), (u_shape(), 3), (star(5), 4)] {
+            let pieces = outline.convex_decomposition(Decomposition::HertelMehlhorn);
+            assert!(pieces.len() <= 4 * optimal);
+            assert_convex_cover(outline, &pieces);
+        }
+    }
+
+    #[test]
+    fn minimal() {
+        for (outline, optimal) in &[(l_shape(), 2), (u_shape(), 3), (star(5), 4)] {
+            let pieces = outline.convex_decomposition(Decomposition::Minimal);
+            assert_eq!(pieces.len(), *optimal);
+            assert_convex_cover(outline, &pieces);
+        }
+    }
+
+    #[test]
+    fn hertel_mehlhorn_is_deterministic() {
+        let star = star(15);
+        let pieces = star.convex_decomposition(Decomposition::HertelMehlhorn);
+        assert_convex_cover(&star, &pieces);
+        let indices: Vec<Vec<usize>> = pieces
+            .iter()
+            .map(|piece| {
+                let position = |&p: &Vec2| star.vertices().iter().position(|&q| q == p).unwrap();
+                piece.vertices().iter().map(position).collect()
+            })
+            .collect();
+        // Every tip is a triangle and inner vertices make a single piece.
+        let mut expected = vec![vec![29, 0, 1]];
+        expected.extend((1..15).map(|k| vec![2 * k - 1, 2 * k, 2 * k + 1]));
+        expected.push(vec![27, 29, 1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25]);
+        assert_eq!(indices, expected);
+        for _ in 0..10 {
+            assert_eq!(
+                star.convex_decomposition(Decomposition::HertelMehlhorn),
+                pieces
+            );
+        }
+    }
+
+    #[test]
+    fn integer_points() {
+        // Offset beyond `f32` precision doesn't affect integer outlines.
+        let base = 1i64 << 40;
+        let u_shape = u_shape().map(|p| [base + p.x() as i64, base + p.y() as i64]);
+        for &mode in &[Decomposition::HertelMehlhorn, Decomposition::Minimal] {
+            let pieces = u_shape.convex_decomposition(mode);
+            let mut area = 0i128;
+            for piece in &pieces {
+                for i in 0..piece.len() as isize {
+                    let (prev, that, next) = piece.prev_that_next(i);
+                    assert_ne!(Point::orient(prev, that, next), Ordering::Less);
+                }
+                area += piece.doubled_signed_area();
+            }
+            assert_eq!(area, u_shape.doubled_signed_area());
+        }
+        let minimal = u_shape.convex_decomposition(Decomposition::Minimal);
+        assert_eq!(minimal.len(), 3);
+    }
+}
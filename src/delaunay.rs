@@ -0,0 +1,718 @@
+use crate::geometry::{circumcenter, cmp_length, incircle, orient};
+use crate::hull::convex_hull;
+use crate::outline::Outline;
+use crate::polygon::Polygon;
+use glam::Vec2;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Marks absence of neighbor triangle.
+const NONE: usize = usize::MAX;
+
+/// Triangle mesh: vertex buffer with triples of indices into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    vertices: Vec<Vec2>,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl Mesh {
+    /// Vertex buffer. Starts with vertices of triangulated shape, followed by inserted points.
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+
+    /// Counter-clockwise triangles as triples of indices into [`Mesh::vertices`].
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// Splits mesh into vertex buffer and triangles.
+    pub fn into_parts(self) -> (Vec<Vec2>, Vec<[usize; 3]>) {
+        (self.vertices, self.triangles)
+    }
+}
+
+/// Quality requirements for Delaunay refinement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Refinement {
+    /// Minimal angle of triangle in radians. Values above 20.7 degrees may prevent
+    /// termination, so refinement stops after `max_steiner_points` insertions.
+    pub min_angle: f32,
+    /// Maximal area of triangle.
+    pub max_area: Option<f32>,
+    /// Upper limit of points inserted into mesh.
+    pub max_steiner_points: usize,
+}
+
+impl Default for Refinement {
+    fn default() -> Self {
+        Refinement {
+            min_angle: 20f32.to_radians(),
+            max_area: None,
+            max_steiner_points: 10000,
+        }
+    }
+}
+
+impl Outline {
+    /// Constrained Delaunay triangulation. Edges of outline are kept as constraints.
+    /// Returns counter-clockwise triangles as triples of vertex indices.
+    pub fn delaunay(&self) -> Vec<[usize; 3]> {
+        let mut cdt = Cdt::new(self.vertices().to_vec(), self.triangulate());
+        cdt.legalize_all();
+        cdt.triangles
+    }
+
+    /// Constrained Delaunay triangulation refined with Ruppert's algorithm: circumcenters of
+    /// bad triangles and midpoints of encroached edges are inserted until every triangle
+    /// satisfies `refinement`. Triangles with small angle between two outline edges are
+    /// accepted as is.
+    pub fn refined_delaunay(&self, refinement: Refinement) -> Mesh {
+        let mut cdt = Cdt::new(self.vertices().to_vec(), self.triangulate());
+        cdt.legalize_all();
+        cdt.refine(refinement);
+        cdt.into_mesh()
+    }
+}
+
+impl Polygon {
+    /// Constrained Delaunay triangulation. Edges of outline and holes are kept as constraints.
+    /// Returns triples of indices into vertex buffer produced by [`Polygon::vertices`].
+    pub fn delaunay(&self) -> Vec<[usize; 3]> {
+        let mut cdt = Cdt::new(self.vertices().collect(), self.triangulate());
+        cdt.legalize_all();
+        cdt.triangles
+    }
+
+    /// Constrained Delaunay triangulation refined with Ruppert's algorithm.
+    /// See [`Outline::refined_delaunay`].
+    pub fn refined_delaunay(&self, refinement: Refinement) -> Mesh {
+        let mut cdt = Cdt::new(self.vertices().collect(), self.triangulate());
+        cdt.legalize_all();
+        cdt.refine(refinement);
+        cdt.into_mesh()
+    }
+}
+
+/// Delaunay triangulation of point set. Returns counter-clockwise triangles as triples of
+/// indices into `points`. Repeated points are used once, collinear points give no triangles.
+/// Points **MUST** be finite.
+pub(crate) fn point_delaunay(points: &[Vec2]) -> Vec<[usize; 3]> {
+    let hull = convex_hull(points.iter().copied());
+    if hull.len() < 3 {
+        return Vec::new();
+    }
+    // The first index of every distinct point.
+    let key = |p: Vec2| ((p.x() + 0f32).to_bits(), (p.y() + 0f32).to_bits());
+    let mut first = HashMap::with_capacity(points.len());
+    for (i, &p) in points.iter().enumerate() {
+        first.entry(key(p)).or_insert(i);
+    }
+    let corners: Vec<usize> = hull.vertices().iter().map(|&p| first[&key(p)]).collect();
+    let fan = (1..corners.len() - 1)
+        .map(|i| [corners[0], corners[i], corners[i + 1]])
+        .collect();
+    let mut cdt = Cdt::new(points.to_vec(), fan);
+    cdt.legalize_all();
+
+    let corners: HashSet<usize> = corners.into_iter().collect();
+    let mut inner: Vec<usize> = (0..points.len())
+        .filter(|&i| first[&key(points[i])] == i && !corners.contains(&i))
+        .collect();
+    // Neighbor points in sorted order are close, so walks are short.
+    inner.sort_by(|&i, &j| {
+        let (p, q) = (points[i], points[j]);
+        (p.x(), p.y()).partial_cmp(&(q.x(), q.y())).unwrap()
+    });
+    let mut last = 0;
+    for i in inner {
+        let legalize = match cdt.locate(points[i], last) {
+            Location::Inside(t) => {
+                last = t;
+                cdt.split_triangle(t, i)
+            }
+            Location::OnEdge(t, edge) => {
+                last = t;
+                cdt.split_edge(t, edge, i)
+            }
+            Location::Vertex | Location::Blocked(..) | Location::Outside => continue,
+        };
+        cdt.legalize(legalize);
+    }
+    cdt.triangles
+}
+
+/// Result of point location.
+enum Location {
+    /// Strictly inside of triangle.
+    Inside(usize),
+    /// On edge of triangle.
+    OnEdge(usize, usize),
+    /// Coincides with existing vertex.
+    Vertex,
+    /// Path to point crosses constrained edge of triangle.
+    Blocked(usize, usize),
+    /// Not covered by any triangle.
+    Outside,
+}
+
+/// Triangulation with adjacency. Edge `i` of triangle connects its `i`-th and `i+1`-th vertices.
+/// Boundary edges are always constrained.
+struct Cdt {
+    points: Vec<Vec2>,
+    triangles: Vec<[usize; 3]>,
+    adjacent: Vec<[usize; 3]>,
+    constrained: Vec<[bool; 3]>,
+    /// Triangles changed since the last refinement step.
+    touched: Vec<usize>,
+}
+
+impl Cdt {
+    fn new(points: Vec<Vec2>, triangles: Vec<[usize; 3]>) -> Self {
+        let mut edges = HashMap::with_capacity(triangles.len() * 3);
+        for (t, tri) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                edges.insert((tri[i], tri[(i + 1) % 3]), t);
+            }
+        }
+        let mut adjacent = vec![[NONE; 3]; triangles.len()];
+        let mut constrained = vec![[false; 3]; triangles.len()];
+        for (t, tri) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                match edges.get(&(tri[(i + 1) % 3], tri[i])) {
+                    Some(&u) => adjacent[t][i] = u,
+                    None => constrained[t][i] = true,
+                }
+            }
+        }
+        Cdt {
+            points,
+            triangles,
+            adjacent,
+            constrained,
+            touched: Vec::new(),
+        }
+    }
+
+    fn into_mesh(self) -> Mesh {
+        Mesh {
+            vertices: self.points,
+            triangles: self.triangles,
+        }
+    }
+
+    fn point(&self, t: usize, i: usize) -> Vec2 {
+        self.points[self.triangles[t][i % 3]]
+    }
+
+    /// Index of edge (`a`, `b`) in triangle `t`.
+    fn edge_index(&self, t: usize, a: usize, b: usize) -> usize {
+        let tri = self.triangles[t];
+        (0..3)
+            .find(|&i| tri[i] == a && tri[(i + 1) % 3] == b)
+            .expect("edge must belong to triangle")
+    }
+
+    /// Makes `new` neighbor of triangle `t` across edge (`a`, `b`).
+    fn relink(&mut self, t: usize, a: usize, b: usize, new: usize) {
+        if t != NONE {
+            let i = self.edge_index(t, a, b);
+            self.adjacent[t][i] = new;
+        }
+    }
+
+    fn set(&mut self, t: usize, tri: [usize; 3], adjacent: [usize; 3], constrained: [bool; 3]) {
+        self.touched.push(t);
+        if t == self.triangles.len() {
+            self.triangles.push(tri);
+            self.adjacent.push(adjacent);
+            self.constrained.push(constrained);
+        } else {
+            self.triangles[t] = tri;
+            self.adjacent[t] = adjacent;
+            self.constrained[t] = constrained;
+        }
+    }
+
+    fn legalize_all(&mut self) {
+        let edges = (0..self.triangles.len())
+            .flat_map(|t| (0..3).map(move |i| (t, i)))
+            .collect();
+        self.legalize(edges);
+    }
+
+    /// Flips non-Delaunay unconstrained edges until none left.
+    fn legalize(&mut self, mut stack: Vec<(usize, usize)>) {
+        while let Some((t, i)) = stack.pop() {
+            let u = self.adjacent[t][i];
+            if u == NONE || self.constrained[t][i] {
+                continue;
+            }
+            let tri = self.triangles[t];
+            let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+            let j = self.edge_index(u, b, a);
+            let d = self.triangles[u][(j + 2) % 3];
+            let (pa, pb, pc, pd) = (
+                self.points[a],
+                self.points[b],
+                self.points[c],
+                self.points[d],
+            );
+            if incircle(pa, pb, pc, pd) <= 0f64
+                || orient(pc, pa, pd) <= 0f64
+                || orient(pd, pb, pc) <= 0f64
+            {
+                continue;
+            }
+            self.flip(t, i, u, j);
+            stack.extend_from_slice(&[(t, 0), (t, 1), (u, 0), (u, 1)]);
+        }
+    }
+
+    /// Replaces triangles (`a`, `b`, `c`) and (`b`, `a`, `d`) sharing edge `i` of `t` and edge
+    /// `j` of `u` with (`c`, `a`, `d`) and (`d`, `b`, `c`).
+    fn flip(&mut self, t: usize, i: usize, u: usize, j: usize) {
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+        let d = self.triangles[u][(j + 2) % 3];
+        let (n_bc, n_ca) = (self.adjacent[t][(i + 1) % 3], self.adjacent[t][(i + 2) % 3]);
+        let (c_bc, c_ca) = (
+            self.constrained[t][(i + 1) % 3],
+            self.constrained[t][(i + 2) % 3],
+        );
+        let (n_ad, n_db) = (self.adjacent[u][(j + 1) % 3], self.adjacent[u][(j + 2) % 3]);
+        let (c_ad, c_db) = (
+            self.constrained[u][(j + 1) % 3],
+            self.constrained[u][(j + 2) % 3],
+        );
+        self.set(t, [c, a, d], [n_ca, n_ad, u], [c_ca, c_ad, false]);
+        self.set(u, [d, b, c], [n_db, n_bc, t], [c_db, c_bc, false]);
+        self.relink(n_ad, d, a, t);
+        self.relink(n_bc, c, b, u);
+    }
+
+    /// Walks from triangle `start` towards `p`. Falls back to [`Cdt::scan`], if walk doesn't
+    /// reach `p` in number of steps equal to number of triangles.
+    fn locate(&self, p: Vec2, start: usize) -> Location {
+        let mut t = start;
+        let mut steps = 0;
+        'walk: while steps <= self.triangles.len() {
+            steps += 1;
+            let mut on_edge = None;
+            for k in 0..3 {
+                // Rotate starting edge to avoid cycling on degenerate configurations.
+                let i = (k + steps) % 3;
+                let side = orient(self.point(t, i), self.point(t, i + 1), p);
+                if side < 0f64 {
+                    if self.constrained[t][i] {
+                        return Location::Blocked(t, i);
+                    }
+                    t = self.adjacent[t][i];
+                    continue 'walk;
+                } else if side == 0f64 {
+                    on_edge = Some(i);
+                }
+            }
+            if (0..3).any(|i| self.point(t, i) == p) {
+                return Location::Vertex;
+            }
+            return match on_edge {
+                Some(i) => Location::OnEdge(t, i),
+                None => Location::Inside(t),
+            };
+        }
+        self.scan(p)
+    }
+
+    /// Finds triangle containing `p` by test of every triangle.
+    fn scan(&self, p: Vec2) -> Location {
+        for t in 0..self.triangles.len() {
+            let sides = [0, 1, 2].map(|i| orient(self.point(t, i), self.point(t, i + 1), p));
+            if sides.iter().any(|&side| side < 0f64) {
+                continue;
+            }
+            if (0..3).any(|i| self.point(t, i) == p) {
+                return Location::Vertex;
+            }
+            return match sides.iter().position(|&side| side == 0f64) {
+                Some(i) => Location::OnEdge(t, i),
+                None => Location::Inside(t),
+            };
+        }
+        Location::Outside
+    }
+
+    /// Inserts new point into triangle `t` and returns triangles to legalize.
+    fn split_triangle(&mut self, t: usize, p: usize) -> Vec<(usize, usize)> {
+        let [a, b, c] = self.triangles[t];
+        let [n_ab, n_bc, n_ca] = self.adjacent[t];
+        let [c_ab, c_bc, c_ca] = self.constrained[t];
+        let t1 = self.triangles.len();
+        let t2 = t1 + 1;
+        self.set(t, [a, b, p], [n_ab, t1, t2], [c_ab, false, false]);
+        self.set(t1, [b, c, p], [n_bc, t2, t], [c_bc, false, false]);
+        self.set(t2, [c, a, p], [n_ca, t, t1], [c_ca, false, false]);
+        self.relink(n_bc, c, b, t1);
+        self.relink(n_ca, a, c, t2);
+        vec![(t, 0), (t1, 0), (t2, 0)]
+    }
+
+    /// Inserts new point on edge `i` of triangle `t` and returns triangles to legalize.
+    fn split_edge(&mut self, t: usize, i: usize, p: usize) -> Vec<(usize, usize)> {
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+        let constrained = self.constrained[t][i];
+        let (n_bc, n_ca) = (self.adjacent[t][(i + 1) % 3], self.adjacent[t][(i + 2) % 3]);
+        let (c_bc, c_ca) = (
+            self.constrained[t][(i + 1) % 3],
+            self.constrained[t][(i + 2) % 3],
+        );
+        let u = self.adjacent[t][i];
+        let t2 = self.triangles.len();
+        let u2 = if u == NONE { NONE } else { t2 + 1 };
+
+        self.set(t, [a, p, c], [u2, t2, n_ca], [constrained, false, c_ca]);
+        self.set(t2, [p, b, c], [u, n_bc, t], [constrained, c_bc, false]);
+        self.relink(n_bc, c, b, t2);
+        let mut legalize = vec![(t, 2), (t2, 1)];
+
+        if u != NONE {
+            let j = self.edge_index(u, b, a);
+            let d = self.triangles[u][(j + 2) % 3];
+            let (n_ad, n_db) = (self.adjacent[u][(j + 1) % 3], self.adjacent[u][(j + 2) % 3]);
+            let (c_ad, c_db) = (
+                self.constrained[u][(j + 1) % 3],
+                self.constrained[u][(j + 2) % 3],
+            );
+            self.set(u, [b, p, d], [t2, u2, n_db], [constrained, false, c_db]);
+            self.set(u2, [p, a, d], [t, n_ad, u], [constrained, c_ad, false]);
+            self.relink(n_ad, d, a, u2);
+            legalize.extend_from_slice(&[(u, 2), (u2, 1)]);
+        }
+        legalize
+    }
+
+    fn add_point(&mut self, p: Vec2) -> usize {
+        self.points.push(p);
+        self.points.len() - 1
+    }
+
+    /// Splits constrained edge `i` of triangle `t` at its midpoint.
+    fn split_segment(&mut self, t: usize, i: usize) -> bool {
+        let (a, b) = (self.point(t, i), self.point(t, i + 1));
+        let mid = (a + b) * 0.5f32;
+        if mid == a || mid == b {
+            return false;
+        }
+        let p = self.add_point(mid);
+        let legalize = self.split_edge(t, i, p);
+        self.legalize(legalize);
+        true
+    }
+
+    /// Test if `p` lies inside of diametral circle of edge `i` of triangle `t`.
+    fn encroaches(&self, t: usize, i: usize, p: Vec2) -> bool {
+        let (a, b) = (self.point(t, i), self.point(t, i + 1));
+        (a - p).dot(b - p) < 0f32
+    }
+
+    /// Constrained edges, which would be connected to `p` inserted into triangle `t`: boundary
+    /// of triangles with `p` inside of circumcircle, reachable from `t` across free edges.
+    fn cavity_segments(&self, t: usize, p: Vec2) -> Vec<(usize, usize)> {
+        let mut segments = Vec::new();
+        let mut visited = HashSet::new();
+        visited.insert(t);
+        let mut stack = vec![t];
+        while let Some(t) = stack.pop() {
+            for i in 0..3 {
+                let u = self.adjacent[t][i];
+                if self.constrained[t][i] {
+                    segments.push((t, i));
+                } else if u != NONE && !visited.contains(&u) {
+                    let inside = incircle(self.point(u, 0), self.point(u, 1), self.point(u, 2), p);
+                    if inside > 0f64 {
+                        visited.insert(u);
+                        stack.push(u);
+                    }
+                }
+            }
+        }
+        segments
+    }
+
+    /// Vertex index and value of the smallest angle of triangle `t`. The smallest angle is
+    /// opposite to the shortest edge, so it is chosen exactly.
+    fn min_angle(&self, t: usize) -> (usize, f32) {
+        let opposite = |i: usize| (self.point(t, i + 1), self.point(t, i + 2));
+        let i = (0..3)
+            .min_by(|&i, &j| {
+                let ((a, b), (c, d)) = (opposite(i), opposite(j));
+                cmp_length(a, b, c, d)
+            })
+            .unwrap();
+        let that = self.point(t, i);
+        let (to_next, to_prev) = (self.point(t, i + 1) - that, self.point(t, i + 2) - that);
+        (i, to_next.perp_dot(to_prev).atan2(to_next.dot(to_prev)))
+    }
+
+    fn is_bad(&self, t: usize, refinement: &Refinement) -> bool {
+        if let Some(max_area) = refinement.max_area {
+            let area = 0.5f64 * orient(self.point(t, 0), self.point(t, 1), self.point(t, 2));
+            if area > f64::from(max_area) {
+                return true;
+            }
+        }
+        let (i, angle) = self.min_angle(t);
+        // Small angle between two constrained edges can't be improved.
+        let input_corner = self.constrained[t][i] && self.constrained[t][(i + 2) % 3];
+        angle < refinement.min_angle && !input_corner
+    }
+
+    /// Moves triangles changed since the last call into queues of candidate bad triangles
+    /// and encroached segments.
+    fn enqueue_touched(
+        &mut self,
+        segments: &mut VecDeque<(usize, usize)>,
+        bad: &mut VecDeque<usize>,
+        refinement: &Refinement,
+    ) {
+        let mut touched = std::mem::take(&mut self.touched);
+        touched.sort_unstable();
+        touched.dedup();
+        for &t in &touched {
+            for i in 0..3 {
+                if self.constrained[t][i] && self.encroaches(t, i, self.point(t, i + 2)) {
+                    segments.push_back((t, i));
+                }
+            }
+            if self.is_bad(t, refinement) {
+                bad.push_back(t);
+            }
+        }
+        touched.clear();
+        self.touched = touched;
+    }
+
+    /// Ruppert's refinement. Only triangles changed by insertions are checked again, so every
+    /// step takes time proportional to size of change.
+    fn refine(&mut self, refinement: Refinement) {
+        let limit = self.points.len() + refinement.max_steiner_points;
+        let mut skipped = HashSet::new();
+        let mut segments = VecDeque::new();
+        let mut bad = VecDeque::new();
+        self.touched = (0..self.triangles.len()).collect();
+        while self.points.len() < limit {
+            self.enqueue_touched(&mut segments, &mut bad, &refinement);
+            // Queued segments and triangles may be changed since, so they are tested again.
+            if let Some((t, i)) = segments.pop_front() {
+                if self.constrained[t][i]
+                    && self.encroaches(t, i, self.point(t, i + 2))
+                    && !self.split_segment(t, i)
+                {
+                    return;
+                }
+                continue;
+            }
+            let t = match bad.pop_front() {
+                Some(t) => t,
+                None => return,
+            };
+            let mut key = self.triangles[t];
+            key.sort_unstable();
+            if skipped.contains(&key) || !self.is_bad(t, &refinement) {
+                continue;
+            }
+            let center = circumcenter(self.point(t, 0), self.point(t, 1), self.point(t, 2));
+            let location = self.locate(center, t);
+            if let Location::Inside(found) | Location::OnEdge(found, _) = location {
+                // Segment encroached by circumcenter is split instead.
+                let encroached = self
+                    .cavity_segments(found, center)
+                    .into_iter()
+                    .find(|&(st, si)| self.encroaches(st, si, center));
+                if let Some((st, si)) = encroached {
+                    if !self.split_segment(st, si) {
+                        return;
+                    }
+                    continue;
+                }
+            }
+            let legalize = match location {
+                Location::Inside(found) => {
+                    let p = self.add_point(center);
+                    self.split_triangle(found, p)
+                }
+                Location::OnEdge(found, i) => {
+                    let p = self.add_point(center);
+                    self.split_edge(found, i, p)
+                }
+                Location::Blocked(found, i) => {
+                    if !self.split_segment(found, i) {
+                        return;
+                    }
+                    continue;
+                }
+                Location::Vertex | Location::Outside => {
+                    skipped.insert(key);
+                    continue;
+                }
+            };
+            self.legalize(legalize);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cdt, Location, Refinement};
+    use crate::geometry::{incircle, orient};
+    use crate::outline::Outline;
+    use crate::polygon::Polygon;
+    use glam::Vec2;
+    use std::collections::HashSet;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    fn area(points: &[Vec2], triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|t| orient(points[t[0]], points[t[1]], points[t[2]]) as f32 * 0.5f32)
+            .sum()
+    }
+
+    fn directed_edges(triangles: &[[usize; 3]]) -> HashSet<(usize, usize)> {
+        triangles
+            .iter()
+            .flat_map(|t| (0..3).map(move |i| (t[i], t[(i + 1) % 3])))
+            .collect()
+    }
+
+    /// Every unconstrained edge must be locally Delaunay.
+    fn assert_delaunay(points: &[Vec2], triangles: &[[usize; 3]]) {
+        for t in triangles {
+            for u in triangles {
+                for i in 0..3 {
+                    let (a, b, c) = (t[i], t[(i + 1) % 3], t[(i + 2) % 3]);
+                    if let Some(j) = (0..3).find(|&j| u[j] == b && u[(j + 1) % 3] == a) {
+                        let d = u[(j + 2) % 3];
+                        let scale = (points[a] - points[b]).length_squared() as f64;
+                        let value = incircle(points[a], points[b], points[c], points[d]);
+                        assert!(value <= 1e-4 * scale * scale);
+                    }
+                }
+            }
+        }
+    }
+
+    fn zigzag() -> Outline {
+        outline(&[
+            (0f32, 0f32),
+            (10f32, 0f32),
+            (10f32, 1f32),
+            (6f32, 1.2f32),
+            (10f32, 3f32),
+            (0f32, 3f32),
+            (4f32, 1.5f32),
+            (0f32, 1f32),
+        ])
+    }
+
+    #[test]
+    fn constrained_delaunay() {
+        let outline = zigzag();
+        let triangles = outline.delaunay();
+        assert_eq!(triangles.len(), outline.len() - 2);
+        assert_delaunay(outline.vertices(), &triangles);
+        let edges = directed_edges(&triangles);
+        for i in 0..outline.len() {
+            assert!(edges.contains(&(i, (i + 1) % outline.len())));
+        }
+        let actual = area(outline.vertices(), &triangles);
+        assert!((actual - outline.signed_area()).abs() < 1e-4f32);
+    }
+
+    #[test]
+    fn constrained_delaunay_with_holes() {
+        let polygon = Polygon::new(
+            outline(&[(0f32, 0f32), (8f32, 0f32), (8f32, 8f32), (0f32, 8f32)]),
+            vec![outline(&[
+                (2f32, 2f32),
+                (2f32, 3f32),
+                (6f32, 3f32),
+                (6f32, 2f32),
+            ])],
+        )
+        .unwrap();
+        let points: Vec<Vec2> = polygon.vertices().collect();
+        let triangles = polygon.delaunay();
+        assert_delaunay(&points, &triangles);
+        assert!((area(&points, &triangles) - polygon.area()).abs() < 1e-4f32);
+    }
+
+    #[test]
+    fn refinement() {
+        let outline = zigzag();
+        let refinement = Refinement {
+            max_area: Some(0.5f32),
+            ..Default::default()
+        };
+        let mesh = outline.refined_delaunay(refinement);
+        let points = mesh.vertices();
+        assert!(points.len() > outline.len());
+        assert_eq!(&points[..outline.len()], outline.vertices());
+        assert_delaunay(points, mesh.triangles());
+        let actual = area(points, mesh.triangles());
+        assert!((actual - outline.signed_area()).abs() < 1e-3f32);
+
+        for t in mesh.triangles() {
+            let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+            assert!(orient(a, b, c) as f32 * 0.5f32 <= 0.5f32);
+            for k in 0..3 {
+                let (p, q, r) = (t[k], t[(k + 1) % 3], t[(k + 2) % 3]);
+                // Input corners sharper than `min_angle` can't be improved.
+                let sharp =
+                    p < outline.len() && outline.inner_angle(p as isize) < refinement.min_angle;
+                let angle = (points[q] - points[p]).angle_between(points[r] - points[p]);
+                assert!(sharp || angle >= refinement.min_angle - 1e-3f32);
+            }
+        }
+    }
+
+    #[test]
+    fn scan_fallback() {
+        let square = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        let cdt = Cdt::new(square.vertices().to_vec(), square.triangulate());
+        assert!(matches!(cdt.scan(Vec2::new(3f32, 1f32)), Location::Outside));
+        assert!(matches!(cdt.scan(Vec2::new(2f32, 2f32)), Location::Vertex));
+        assert!(matches!(
+            cdt.scan(Vec2::new(0.5f32, 0.2f32)),
+            Location::Inside(_)
+        ));
+        assert!(matches!(
+            cdt.scan(Vec2::new(1f32, 0f32)),
+            Location::OnEdge(..)
+        ));
+    }
+
+    #[test]
+    fn refinement_of_circle() {
+        let circle = Outline::new((0..200).map(|i| {
+            let angle = i as f32 * std::f32::consts::PI / 100f32;
+            Vec2::new(angle.cos(), angle.sin()) * 100f32
+        }));
+        let mesh = circle.refined_delaunay(Default::default());
+        let points = mesh.vertices();
+        assert_delaunay(points, mesh.triangles());
+        let actual = area(points, mesh.triangles());
+        assert!((actual - circle.signed_area()).abs() < 1e-1f32);
+        for t in mesh.triangles() {
+            let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+            for &(p, q, r) in &[(a, b, c), (b, c, a), (c, a, b)] {
+                let angle = (q - p).angle_between(r - p);
+                assert!(angle >= Refinement::default().min_angle - 1e-3f32);
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Nearest points of outline boundary and signed distance to outline.
+use crate::geometry::closest_on_segment;
+use crate::outline::Outline;
+use crate::prepared::PreparedOutline;
+use glam::Vec2;
+
+/// The nearest to query point on outline boundary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestPoint {
+    /// Point on boundary.
+    pub point: Vec2,
+    /// Index of edge containing `point` in range `0..len`.
+    pub edge: usize,
+    /// Position of `point` on edge from `0` at its start to `1` at its end.
+    pub parameter: f32,
+    /// Distance from query point to `point`. Negative if query point is inside of outline.
+    pub signed_distance: f32,
+}
+
+fn xy(p: Vec2) -> [f64; 2] {
+    [f64::from(p.x()), f64::from(p.y())]
+}
+
+impl Outline {
+    /// The nearest to `point` point of edge and its parameter on edge in range `[0, 1]`.
+    /// # Arguments
+    /// * `i` - index of edge. May be negative;
+    pub fn closest_point_on_edge(&self, point: Vec2, i: isize) -> (Vec2, f32) {
+        let (a, b) = self.edge(i);
+        let (t, _) = closest_on_segment(xy(a), xy(b), xy(point));
+        let t = t as f32;
+        // Endpoints are returned exactly.
+        let closest = if t == 0f32 {
+            a
+        } else if t == 1f32 {
+            b
+        } else {
+            a + (b - a) * t
+        };
+        (closest, t)
+    }
+
+    fn closest(&self, point: Vec2, edge: usize, inside: bool) -> ClosestPoint {
+        let (closest, parameter) = self.closest_point_on_edge(point, edge as isize);
+        let distance = (closest - point).length();
+        ClosestPoint {
+            point: closest,
+            edge,
+            parameter,
+            signed_distance: if inside { -distance } else { distance },
+        }
+    }
+
+    /// The nearest to `point` point of boundary. Edge with the least index is chosen if
+    /// several are equally near. `None` for empty outline. Inside is decided by crossing
+    /// number rule, as [`Outline::contains`] does.
+    pub fn closest_point(&self, point: Vec2) -> Option<ClosestPoint> {
+        let p = xy(point);
+        let distance = |(a, b): (Vec2, Vec2)| closest_on_segment(xy(a), xy(b), p).1;
+        let (edge, _) = self.edges().map(distance).enumerate().fold(
+            None,
+            |best: Option<(usize, f64)>, (i, d)| match best {
+                Some((_, best_d)) if best_d <= d => best,
+                _ => Some((i, d)),
+            },
+        )?;
+        Some(self.closest(point, edge, self.contains(point)))
+    }
+
+    /// Distance from `point` to boundary, negative inside of outline. Infinite for empty
+    /// outline. See [`Outline::closest_point`].
+    pub fn signed_distance(&self, point: Vec2) -> f32 {
+        self.closest_point(point)
+            .map_or(f32::INFINITY, |closest| closest.signed_distance)
+    }
+}
+
+impl PreparedOutline {
+    /// The nearest to `point` point of boundary. See [`Outline::closest_point`].
+    pub fn closest_point(&self, point: Vec2) -> Option<ClosestPoint> {
+        let (edge, _) = self.nearest_edge(point)?;
+        Some(self.outline().closest(point, edge, self.contains(point)))
+    }
+
+    /// Distance from `point` to boundary, negative inside of outline.
+    /// See [`Outline::signed_distance`].
+    pub fn signed_distance(&self, point: Vec2) -> f32 {
+        self.closest_point(point)
+            .map_or(f32::INFINITY, |closest| closest.signed_distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClosestPoint;
+    use crate::outline::Outline;
+    use crate::prepared::PreparedOutline;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn closest_point() {
+        let square = outline(&[(0f32, 0f32), (4f32, 0f32), (4f32, 4f32), (0f32, 4f32)]);
+        assert_eq!(
+            square.closest_point(Vec2::new(1f32, 1.5f32)),
+            Some(ClosestPoint {
+                point: Vec2::new(0f32, 1.5f32),
+                edge: 3,
+                parameter: 0.625f32,
+                signed_distance: -1f32,
+            })
+        );
+        assert_eq!(
+            square.closest_point(Vec2::new(5f32, 3f32)),
+            Some(ClosestPoint {
+                point: Vec2::new(4f32, 3f32),
+                edge: 1,
+                parameter: 0.75f32,
+                signed_distance: 1f32,
+            })
+        );
+        // Corner belongs to edges 0 and 1, the first one is chosen.
+        let corner = square.closest_point(Vec2::new(5f32, -1f32)).unwrap();
+        assert_eq!((corner.point, corner.edge), (Vec2::new(4f32, 0f32), 0));
+        assert_eq!(corner.parameter, 1f32);
+        assert_eq!(square.signed_distance(Vec2::new(2f32, 2f32)), -2f32);
+
+        let empty = Outline::new(Vec::<Vec2>::new().into_iter());
+        assert_eq!(empty.closest_point(Vec2::new(0f32, 0f32)), None);
+        assert_eq!(empty.signed_distance(Vec2::new(0f32, 0f32)), f32::INFINITY);
+    }
+
+    #[test]
+    fn negative_edge_index() {
+        let square = outline(&[(0f32, 0f32), (4f32, 0f32), (4f32, 4f32), (0f32, 4f32)]);
+        let point = Vec2::new(-1f32, 3f32);
+        assert_eq!(
+            square.closest_point_on_edge(point, -1),
+            square.closest_point_on_edge(point, 3)
+        );
+        assert_eq!(
+            square.closest_point_on_edge(point, -1),
+            (Vec2::new(0f32, 3f32), 0.25f32)
+        );
+        assert_eq!(
+            square.closest_point_on_edge(point, 4),
+            (Vec2::new(0f32, 0f32), 0f32)
+        );
+    }
+
+    #[test]
+    fn prepared() {
+        let star = Outline::new((0..40).map(|i| {
+            let angle = i as f32 / 40f32 * std::f32::consts::PI * 2f32;
+            let radius = if i % 2 == 0 { 10f32 } else { 4f32 };
+            Vec2::new(angle.cos() * radius, angle.sin() * radius)
+        }));
+        let prepared = PreparedOutline::new(star.clone());
+        for i in 0..200 {
+            let p = Vec2::new((i % 20) as f32 - 9.5f32, (i / 10) as f32 - 9.7f32);
+            assert_eq!(prepared.closest_point(p), star.closest_point(p));
+            assert_eq!(prepared.signed_distance(p), star.signed_distance(p));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Lossless conversion between [`glam::Vec2`] and integer coordinates.
+//!
+//! Float coordinate `x` becomes integer `round(x * scale)`. Conversion succeeds only if
+//! dividing integer back by `scale` gives the same `f32`, so round trip never moves vertices.
+//! `i64` coordinates are limited by `2^53` in magnitude, so boolean operations on them are exact.
+use crate::outline::Outline;
+use crate::point::EXACT_INTEGER;
+use crate::polygon::Polygon;
+use glam::Vec2;
+use std::error::Error;
+use std::fmt;
+
+/// Reason of failed conversion between float and integer coordinates. Vertex index counts
+/// vertices of all rings, as [`Polygon::vertices`] does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedPointError {
+    /// Scale is not positive finite number.
+    InvalidScale,
+    /// Vertex with specified index doesn't fit into integer type.
+    OutOfRange(usize),
+    /// Vertex with specified index is not restored exactly by conversion back.
+    Inexact(usize),
+}
+
+impl fmt::Display for FixedPointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixedPointError::InvalidScale => write!(f, "scale must be positive and finite"),
+            FixedPointError::OutOfRange(i) => write!(f, "vertex {} is out of range", i),
+            FixedPointError::Inexact(i) => {
+                write!(f, "vertex {} can't be converted without loss", i)
+            }
+        }
+    }
+}
+
+impl Error for FixedPointError {}
+
+fn check_scale(scale: f32) -> Result<f64, FixedPointError> {
+    if scale.is_finite() && scale > 0f32 {
+        Ok(f64::from(scale))
+    } else {
+        Err(FixedPointError::InvalidScale)
+    }
+}
+
+/// Float coordinate restored from integer one.
+fn to_float(value: f64, scale: f64) -> f32 {
+    (value / scale) as f32
+}
+
+/// Converts every vertex by `convert` or reports the first failed one.
+fn convert_all<P: Copy, Q>(
+    vertices: impl Iterator<Item = P>,
+    convert: impl Fn(P) -> Result<Q, bool>,
+) -> Result<Vec<Q>, FixedPointError> {
+    vertices
+        .enumerate()
+        .map(|(i, p)| {
+            convert(p).map_err(|inexact| {
+                if inexact {
+                    FixedPointError::Inexact(i)
+                } else {
+                    FixedPointError::OutOfRange(i)
+                }
+            })
+        })
+        .collect()
+}
+
+/// Generates conversions for integer type `$int`, which keeps coordinates up to `$limit`.
+macro_rules! fixed_conversions {
+    ($($int:ty, $limit:expr, $to_int:ident, $doc:literal;)*) => {$(
+        fn $to_int(p: Vec2, scale: f64) -> Result<[$int; 2], bool> {
+            let coordinate = |x: f32| {
+                let value = (f64::from(x) * scale).round();
+                if value.is_nan() {
+                    return Err(true);
+                }
+                if value.abs() > $limit as f64 {
+                    return Err(false);
+                }
+                if to_float(value, scale) != x {
+                    return Err(true);
+                }
+                Ok(value as $int)
+            };
+            Ok([coordinate(p.x())?, coordinate(p.y())?])
+        }
+
+        impl Outline {
+            #[doc = concat!("Outline with vertices multiplied by `scale` and converted to `",
+                $doc, "`.")]
+            /// Fails if any vertex changes after conversion back by the same `scale`.
+            pub fn $to_int(&self, scale: f32) -> Result<Outline<[$int; 2]>, FixedPointError> {
+                let scale = check_scale(scale)?;
+                let vertices = convert_all(self.vertices().iter().copied(), |p| $to_int(p, scale))?;
+                Ok(Outline::new(vertices.into_iter()))
+            }
+        }
+
+        impl Polygon {
+            #[doc = concat!("Polygon with vertices multiplied by `scale` and converted to `",
+                $doc, "`.")]
+            /// Fails if any vertex changes after conversion back by the same `scale`.
+            pub fn $to_int(&self, scale: f32) -> Result<Polygon<[$int; 2]>, FixedPointError> {
+                let scale = check_scale(scale)?;
+                convert_all(self.vertices(), |p| $to_int(p, scale))?;
+                Ok(self.map(|p| $to_int(p, scale).unwrap()))
+            }
+        }
+
+        impl Outline<[$int; 2]> {
+            /// Outline with vertices divided by `scale`. Fails if any vertex can't be restored
+            /// exactly by conversion back.
+            pub fn to_vec2(&self, scale: f32) -> Result<Outline, FixedPointError> {
+                let scale = check_scale(scale)?;
+                let vertices = convert_all(self.vertices().iter().copied(), |p| to_vec2(p, scale))?;
+                Ok(Outline::new(vertices.into_iter()))
+            }
+        }
+
+        impl Polygon<[$int; 2]> {
+            /// Polygon with vertices divided by `scale`. Fails if any vertex can't be restored
+            /// exactly by conversion back.
+            pub fn to_vec2(&self, scale: f32) -> Result<Polygon, FixedPointError> {
+                let scale = check_scale(scale)?;
+                convert_all(self.vertices(), |p| to_vec2(p, scale))?;
+                Ok(self.map(|p| to_vec2(p, scale).unwrap()))
+            }
+        }
+    )*};
+}
+
+fixed_conversions! {
+    i32, i32::MAX, to_i32, "i32";
+    i64, EXACT_INTEGER, to_i64, "i64";
+}
+
+/// Converts integer point back to float one, if it is restored exactly.
+fn to_vec2<I: Copy + Into<i128>>(p: [I; 2], scale: f64) -> Result<Vec2, bool> {
+    let coordinate = |value: I| {
+        let value: i128 = value.into();
+        let x = to_float(value as f64, scale);
+        if !x.is_finite() {
+            return Err(false);
+        }
+        if (f64::from(x) * scale).round() as i128 != value {
+            return Err(true);
+        }
+        Ok(x)
+    };
+    Ok(Vec2::new(coordinate(p[0])?, coordinate(p[1])?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedPointError;
+    use crate::outline::Outline;
+    use crate::polygon::Polygon;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn round_trip() {
+        let outer = outline(&[
+            (0f32, 0f32),
+            (2.5f32, 0f32),
+            (2.5f32, 1.75f32),
+            (0f32, 1.75f32),
+        ]);
+        let hole = outline(&[
+            (0.5f32, 0.5f32),
+            (0.5f32, 1f32),
+            (1f32, 1f32),
+            (1f32, 0.5f32),
+        ]);
+        let polygon = Polygon::new(outer.clone(), vec![hole]).unwrap();
+
+        let fixed = outer.to_i32(4f32).unwrap();
+        assert_eq!(fixed[2], [10, 7]);
+        assert_eq!(fixed.to_vec2(4f32).unwrap(), outer);
+
+        let fixed = polygon.to_i64(1000f32).unwrap();
+        assert_eq!(fixed.holes()[0][0], [500, 500]);
+        assert_eq!(fixed.to_vec2(1000f32).unwrap(), polygon);
+
+        // Decimal fractions aren't exact in binary, but still restored.
+        let decimal = outline(&[(0.1f32, 0.2f32), (1.3f32, 0.2f32), (1.3f32, 0.7f32)]);
+        let fixed = decimal.to_i32(10f32).unwrap();
+        assert_eq!(fixed[1], [13, 2]);
+        assert_eq!(fixed.to_vec2(10f32).unwrap(), decimal);
+    }
+
+    #[test]
+    fn rejects_lossy_conversion() {
+        let fine = outline(&[(0f32, 0f32), (1f32, 0f32), (1f32, 0.123f32)]);
+        assert_eq!(fine.to_i32(10f32), Err(FixedPointError::Inexact(2)));
+        assert_eq!(fine.to_i32(0f32), Err(FixedPointError::InvalidScale));
+        let huge = outline(&[(0f32, 0f32), (1e10f32, 0f32), (1f32, 1f32)]);
+        assert_eq!(huge.to_i32(1f32), Err(FixedPointError::OutOfRange(1)));
+        assert!(huge.to_i64(1f32).is_ok());
+        let limit = outline(&[(0f32, 0f32), (2f32.powi(53), 0f32), (1f32, 1f32)]);
+        assert!(limit.to_i64(1f32).is_ok());
+        assert_eq!(limit.to_i64(2f32), Err(FixedPointError::OutOfRange(1)));
+
+        // Integer `2^24 + 1` has no exact `f32` representation.
+        let odd = Outline::new(vec![[0i64, 0], [(1 << 24) + 1, 0], [0, 1]].into_iter());
+        assert_eq!(odd.to_vec2(1f32), Err(FixedPointError::Inexact(1)));
+        let even = Outline::new(vec![[0i64, 0], [1 << 24, 0], [0, 1]].into_iter());
+        assert!(even.to_vec2(1f32).is_ok());
+    }
+}
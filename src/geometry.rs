@@ -0,0 +1,429 @@
+use crate::point::Point;
+use glam::Vec2;
+use std::cmp::Ordering;
+
+/// Half of distance between 1.0 and the next `f64`.
+const EPSILON: f64 = f64::EPSILON * 0.5f64;
+/// Relative error bound of [`cross64`] evaluated in floating point.
+const CROSS_BOUND: f64 = (3f64 + 16f64 * EPSILON) * EPSILON;
+/// Relative error bound of [`incircle`] evaluated in floating point.
+const INCIRCLE_BOUND: f64 = (10f64 + 96f64 * EPSILON) * EPSILON;
+
+/// Doubled signed area of triangle (`a`, `b`, `c`). Positive if `c` lies at left side of
+/// directed line from `a` to `b`. Sign is exact, magnitude is approximate.
+pub(crate) fn orient(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+    cross(a, b, a, c)
+}
+
+/// Cross product of vectors `b - a` and `d - c`. Sign is exact, magnitude is approximate.
+pub(crate) fn cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> f64 {
+    let [a, b, c, d] = [a, b, c, d].map(|p| [f64::from(p.x()), f64::from(p.y())]);
+    cross64(a, b, c, d)
+}
+
+/// Cross product of vectors `b - a` and `d - c` with coordinates in `f64`. Sign is exact,
+/// magnitude is approximate.
+pub(crate) fn cross64(a: [f64; 2], b: [f64; 2], c: [f64; 2], d: [f64; 2]) -> f64 {
+    let left = (b[0] - a[0]) * (d[1] - c[1]);
+    let right = (b[1] - a[1]) * (d[0] - c[0]);
+    let approx = left - right;
+    if approx.abs() > CROSS_BOUND * (left.abs() + right.abs()) {
+        return approx;
+    }
+    let (dx1, dx1_tail) = two_sum(b[0], -a[0]);
+    let (dy1, dy1_tail) = two_sum(b[1], -a[1]);
+    let (dx2, dx2_tail) = two_sum(d[0], -c[0]);
+    let (dy2, dy2_tail) = two_sum(d[1], -c[1]);
+    if dx1_tail == 0f64 && dy1_tail == 0f64 && dx2_tail == 0f64 && dy2_tail == 0f64 {
+        // Differences are exact, so only products need extra precision.
+        let (left, left_tail) = two_product(dx1, dy2);
+        let (right, right_tail) = two_product(dy1, dx2);
+        return sum_exact([left_tail, -right_tail, left, -right]);
+    }
+    let diff = |p: [f64; 2], q: [f64; 2], k: usize| Expansion::diff(p[k], q[k]);
+    diff(b, a, 0)
+        .mul(&diff(d, c, 1))
+        .sub(&diff(b, a, 1).mul(&diff(d, c, 0)))
+        .estimate()
+}
+
+/// Test if `p` lies inside of counter-clockwise triangle (`a`, `b`, `c`) or on its border.
+pub(crate) fn in_triangle<P: Point>(a: P, b: P, c: P, p: P) -> bool {
+    [(a, b), (b, c), (c, a)]
+        .iter()
+        .all(|&(from, to)| P::orient(from, to, p) != Ordering::Less)
+}
+
+/// Order of points `p` and `q` by angle of turn around `center` from direction to `from`, in
+/// range `(0, 2π]`. Turn is clockwise, if `clockwise` is `true`, and counter-clockwise otherwise.
+/// Points **MUST** differ from `center`.
+pub(crate) fn cmp_turn<P: Point>(center: P, from: P, p: P, q: P, clockwise: bool) -> Ordering {
+    // Greater if turn from direction to `a` to direction to `b` is less than `π`.
+    let side = |a: P, b: P| {
+        let side = P::orient(center, a, b);
+        if clockwise {
+            side.reverse()
+        } else {
+            side
+        }
+    };
+    let same_direction = |p: P| {
+        p.x().partial_cmp(&center.x()) == from.x().partial_cmp(&center.x())
+            && p.y().partial_cmp(&center.y()) == from.y().partial_cmp(&center.y())
+    };
+    // Turns in range `(π, 2π]`.
+    let second_half = |p: P| match side(from, p) {
+        Ordering::Less => true,
+        Ordering::Equal => same_direction(p),
+        Ordering::Greater => false,
+    };
+    second_half(p)
+        .cmp(&second_half(q))
+        .then_with(|| side(p, q).reverse())
+}
+
+/// Order of squared lengths of segments from `a` to `b` and from `c` to `d`. Exact.
+pub(crate) fn cmp_length(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> Ordering {
+    let squared = |a: Vec2, b: Vec2| {
+        let dx = Expansion::diff(f64::from(b.x()), f64::from(a.x()));
+        let dy = Expansion::diff(f64::from(b.y()), f64::from(a.y()));
+        dx.mul(&dx).add(&dy.mul(&dy))
+    };
+    squared(a, b).sub(&squared(c, d)).sign()
+}
+
+/// Positive if `d` lies inside of circumcircle of counter-clockwise triangle (`a`, `b`, `c`),
+/// negative if outside and zero if on it. Sign is exact, magnitude is approximate.
+pub(crate) fn incircle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> f64 {
+    let [a, b, c, d] = [a, b, c, d].map(|p| [f64::from(p.x()), f64::from(p.y())]);
+    let (adx, ady) = (a[0] - d[0], a[1] - d[1]);
+    let (bdx, bdy) = (b[0] - d[0], b[1] - d[1]);
+    let (cdx, cdy) = (c[0] - d[0], c[1] - d[1]);
+    let alift = adx * adx + ady * ady;
+    let blift = bdx * bdx + bdy * bdy;
+    let clift = cdx * cdx + cdy * cdy;
+    let (bc, cb) = (bdx * cdy, cdx * bdy);
+    let (ca, ac) = (cdx * ady, adx * cdy);
+    let (ab, ba) = (adx * bdy, bdx * ady);
+    let approx = alift * (bc - cb) + blift * (ca - ac) + clift * (ab - ba);
+    let permanent = alift * (bc.abs() + cb.abs())
+        + blift * (ca.abs() + ac.abs())
+        + clift * (ab.abs() + ba.abs());
+    if approx.abs() > INCIRCLE_BOUND * permanent {
+        return approx;
+    }
+
+    let [adx, ady, bdx, bdy, cdx, cdy] = [
+        Expansion::diff(a[0], d[0]),
+        Expansion::diff(a[1], d[1]),
+        Expansion::diff(b[0], d[0]),
+        Expansion::diff(b[1], d[1]),
+        Expansion::diff(c[0], d[0]),
+        Expansion::diff(c[1], d[1]),
+    ];
+    let lift = |x: &Expansion, y: &Expansion| x.mul(x).add(&y.mul(y));
+    let det = |x1: &Expansion, y1: &Expansion, x2: &Expansion, y2: &Expansion| {
+        x1.mul(y2).sub(&x2.mul(y1))
+    };
+    lift(&adx, &ady)
+        .mul(&det(&bdx, &bdy, &cdx, &cdy))
+        .add(&lift(&bdx, &bdy).mul(&det(&cdx, &cdy, &adx, &ady)))
+        .add(&lift(&cdx, &cdy).mul(&det(&adx, &ady, &bdx, &bdy)))
+        .estimate()
+}
+
+/// Center of circle passing through `a`, `b` and `c`.
+pub(crate) fn circumcenter(a: Vec2, b: Vec2, c: Vec2) -> Vec2 {
+    let (bx, by) = (f64::from(b.x() - a.x()), f64::from(b.y() - a.y()));
+    let (cx, cy) = (f64::from(c.x() - a.x()), f64::from(c.y() - a.y()));
+    let d = 2f64 * (bx * cy - by * cx);
+    let b_len = bx * bx + by * by;
+    let c_len = cx * cx + cy * cy;
+    let x = (cy * b_len - by * c_len) / d;
+    let y = (bx * c_len - cx * b_len) / d;
+    a + Vec2::new(x as f32, y as f32)
+}
+
+/// Parameter of the nearest to `p` point of segment from `a` to `b` in range `[0, 1]` and
+/// squared distance to it.
+pub(crate) fn closest_on_segment(a: [f64; 2], b: [f64; 2], p: [f64; 2]) -> (f64, f64) {
+    let d = [b[0] - a[0], b[1] - a[1]];
+    let len = d[0] * d[0] + d[1] * d[1];
+    let t = if len > 0f64 {
+        (((p[0] - a[0]) * d[0] + (p[1] - a[1]) * d[1]) / len).clamp(0f64, 1f64)
+    } else {
+        0f64
+    };
+    let (dx, dy) = (a[0] + d[0] * t - p[0], a[1] + d[1] * t - p[1]);
+    (t, dx * dx + dy * dy)
+}
+
+/// Total order of floats like `f64::total_cmp`: negative NaN, negative numbers, `-0.0`, `0.0`,
+/// positive numbers, positive NaN.
+pub(crate) fn total_cmp(a: f64, b: f64) -> Ordering {
+    let key = |value: f64| {
+        let bits = value.to_bits() as i64;
+        bits ^ (((bits >> 63) as u64) >> 1) as i64
+    };
+    key(a).cmp(&key(b))
+}
+
+/// Exact sum `x + y` of `a + b`, where `x` is rounded sum.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    (x, (a - a_virtual) + (b - b_virtual))
+}
+
+/// Exact product `x + y` of `a * b`, where `x` is rounded product.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, a.mul_add(b, -x))
+}
+
+/// Approximate sum of four numbers with exact sign.
+fn sum_exact(values: [f64; 4]) -> f64 {
+    let mut components = [0f64; 4];
+    let mut len = 0;
+    for &value in &values {
+        // Grow-expansion on stack buffer.
+        let mut carry = value;
+        let mut grown = 0;
+        for k in 0..len {
+            let (x, y) = two_sum(carry, components[k]);
+            if y != 0f64 {
+                components[grown] = y;
+                grown += 1;
+            }
+            carry = x;
+        }
+        if carry != 0f64 {
+            components[grown] = carry;
+            grown += 1;
+        }
+        len = grown;
+    }
+    components[..len].iter().sum()
+}
+
+/// Exact value represented by sum of non-overlapping components sorted by increasing
+/// magnitude, as described by Shewchuk in "Adaptive Precision Floating-Point Arithmetic and
+/// Fast Robust Geometric Predicates". Zero components are eliminated.
+#[derive(Debug, Clone)]
+pub(crate) struct Expansion(Vec<f64>);
+
+impl Expansion {
+    pub(crate) fn new(value: f64) -> Self {
+        Expansion(if value == 0f64 {
+            Vec::new()
+        } else {
+            vec![value]
+        })
+    }
+
+    /// Exact difference of two numbers.
+    pub(crate) fn diff(a: f64, b: f64) -> Self {
+        let (x, y) = two_sum(a, -b);
+        Expansion([y, x].iter().copied().filter(|&c| c != 0f64).collect())
+    }
+
+    pub(crate) fn add(&self, other: &Expansion) -> Self {
+        let mut sum = self.0.clone();
+        for &component in &other.0 {
+            // Grow-expansion: carries `component` through all components of `sum`.
+            let mut carry = component;
+            let mut grown = Vec::with_capacity(sum.len() + 1);
+            for &c in &sum {
+                let (x, y) = two_sum(carry, c);
+                if y != 0f64 {
+                    grown.push(y);
+                }
+                carry = x;
+            }
+            if carry != 0f64 {
+                grown.push(carry);
+            }
+            sum = grown;
+        }
+        Expansion(sum)
+    }
+
+    pub(crate) fn sub(&self, other: &Expansion) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub(crate) fn scale(&self, b: f64) -> Self {
+        let mut result = Vec::with_capacity(self.0.len() * 2);
+        let mut components = self.0.iter();
+        let first = match components.next() {
+            Some(&first) => first,
+            None => return Expansion(result),
+        };
+        let (mut carry, low) = two_product(first, b);
+        result.push(low);
+        for &c in components {
+            let (high, low) = two_product(c, b);
+            let (sum, error) = two_sum(carry, low);
+            result.push(error);
+            let (sum, error) = two_sum(high, sum);
+            result.push(error);
+            carry = sum;
+        }
+        result.push(carry);
+        result.retain(|&c| c != 0f64);
+        Expansion(result)
+    }
+
+    pub(crate) fn mul(&self, other: &Expansion) -> Self {
+        other
+            .0
+            .iter()
+            .fold(Expansion(Vec::new()), |sum, &c| sum.add(&self.scale(c)))
+    }
+
+    /// Approximate value with exact sign.
+    pub(crate) fn estimate(&self) -> f64 {
+        self.0.iter().sum()
+    }
+
+    /// Exact sign of value.
+    pub(crate) fn sign(&self) -> Ordering {
+        self.0
+            .last()
+            .map_or(Ordering::Equal, |c| c.partial_cmp(&0f64).unwrap())
+    }
+
+    pub(crate) fn neg(&self) -> Self {
+        Expansion(self.0.iter().map(|c| -c).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cmp_length, cmp_turn, incircle, orient};
+    use glam::Vec2;
+    use std::cmp::Ordering;
+
+    /// Unit in the last place of `f32` numbers in range [0.5, 1).
+    const ULP: f32 = 1f32 / (1 << 24) as f32;
+
+    fn to_int(p: Vec2) -> (i128, i128) {
+        ((p.x() / ULP) as i128, (p.y() / ULP) as i128)
+    }
+
+    fn sign(value: f64) -> i128 {
+        if value > 0f64 {
+            1
+        } else if value < 0f64 {
+            -1
+        } else {
+            0
+        }
+    }
+
+    fn exact_orient(a: Vec2, b: Vec2, c: Vec2) -> i128 {
+        let ((ax, ay), (bx, by), (cx, cy)) = (to_int(a), to_int(b), to_int(c));
+        (bx - ax) * (cy - ay) - (by - ay) * (cx - ax)
+    }
+
+    #[test]
+    fn orient_near_collinear() {
+        // Points in tiny neighborhood of line y = x, where naive evaluation fails.
+        let b = Vec2::new(12f32, 12f32);
+        let c = Vec2::new(24f32, 24f32);
+        let mut naive_errors = 0;
+        for i in 0..64 {
+            for j in 0..64 {
+                let a = Vec2::new(0.5f32 + i as f32 * ULP, 0.5f32 + j as f32 * ULP);
+                let expected = exact_orient(a, b, c).signum();
+                assert_eq!(sign(orient(a, b, c)), expected);
+                if sign(f64::from((b - a).perp_dot(c - a))) != expected {
+                    naive_errors += 1;
+                }
+            }
+        }
+        assert!(naive_errors > 0);
+    }
+
+    #[test]
+    fn orient_is_consistent_under_permutation() {
+        let a = Vec2::new(0.1f32, 0.1f32);
+        let b = Vec2::new(0.3f32, 0.3f32);
+        for k in -8..8 {
+            let c = Vec2::new(0.7f32, 0.7f32 + k as f32 * ULP);
+            let expected = sign(orient(a, b, c));
+            assert_eq!(sign(orient(b, c, a)), expected);
+            assert_eq!(sign(orient(c, a, b)), expected);
+            assert_eq!(sign(orient(b, a, c)), -expected);
+        }
+    }
+
+    #[test]
+    fn incircle_near_cocircular() {
+        // Corners of square are cocircular, fourth corner is moved by single ulp.
+        let (a, b, c) = (
+            Vec2::new(0.5f32, 0.5f32),
+            Vec2::new(0.75f32, 0.5f32),
+            Vec2::new(0.75f32, 0.75f32),
+        );
+        assert_eq!(incircle(a, b, c, Vec2::new(0.5f32, 0.75f32)), 0f64);
+        for &(dx, dy) in &[(1f32, 0f32), (0f32, -1f32), (1f32, -1f32)] {
+            let d = Vec2::new(0.5f32 + dx * ULP, 0.75f32 + dy * ULP);
+            assert!(incircle(a, b, c, d) > 0f64);
+        }
+        for &(dx, dy) in &[(-1f32, 0f32), (0f32, 1f32), (-1f32, 1f32)] {
+            let d = Vec2::new(0.5f32 + dx * ULP, 0.75f32 + dy * ULP);
+            assert!(incircle(a, b, c, d) < 0f64);
+        }
+    }
+
+    #[test]
+    fn large_coordinates() {
+        let a = Vec2::new(1e30f32, 1e30f32);
+        let b = Vec2::new(-1e30f32, -1e30f32);
+        assert_eq!(orient(a, b, Vec2::new(1e-30f32, 1e-30f32)), 0f64);
+        assert!(orient(a, b, Vec2::new(-1e-30f32, 1e-30f32)) < 0f64);
+        assert!(orient(a, b, Vec2::new(1e-30f32, -1e-30f32)) > 0f64);
+    }
+
+    #[test]
+    fn turn_order() {
+        let center = Vec2::new(0.5f32, 0.5f32);
+        let from = Vec2::new(1f32, 0.5f32);
+        // Counter-clockwise order of directions from the first one after `from` to `from`.
+        let mut points = vec![
+            Vec2::new(1f32, 0.5f32 + ULP),
+            Vec2::new(0.5f32, 1f32),
+            Vec2::new(0f32, 0.5f32),
+            Vec2::new(0f32, 0.5f32 - ULP),
+            Vec2::new(0.5f32, 0f32),
+            Vec2::new(1f32, 0.5f32 - ULP),
+            Vec2::new(2f32, 0.5f32),
+        ];
+        for (i, &p) in points.iter().enumerate() {
+            for (j, &q) in points.iter().enumerate() {
+                assert_eq!(cmp_turn(center, from, p, q, false), i.cmp(&j));
+            }
+        }
+        // Clockwise order is reversed, except for the last direction equal to `from`.
+        let last = points.pop().unwrap();
+        points.reverse();
+        points.push(last);
+        for (i, &p) in points.iter().enumerate() {
+            for (j, &q) in points.iter().enumerate() {
+                assert_eq!(cmp_turn(center, from, p, q, true), i.cmp(&j));
+            }
+        }
+    }
+
+    #[test]
+    fn length_order() {
+        let a = Vec2::new(0.5f32, 0.5f32);
+        let b = Vec2::new(0.5f32 + 3f32 * ULP, 0.5f32 + 4f32 * ULP);
+        let c = Vec2::new(0.5f32 + 5f32 * ULP, 0.5f32);
+        assert_eq!(cmp_length(a, b, a, c), Ordering::Equal);
+        assert_eq!(cmp_length(a, b, b, c), Ordering::Greater);
+        assert_eq!(cmp_length(b, c, c, a), Ordering::Less);
+    }
+}
@@ -0,0 +1,191 @@
+//! Convex hulls of point sets and of outlines.
+//!
+//! Hulls are counter-clockwise, start at lexicographically least vertex and have no
+//! collinear or coincident vertices. Decisions are exact, see [`Point::orient`].
+use crate::outline::Outline;
+use crate::point::Point;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+fn lex<P: Point>(a: &P, b: &P) -> Ordering {
+    let x = a.x().partial_cmp(&b.x()).unwrap_or(Ordering::Equal);
+    x.then(a.y().partial_cmp(&b.y()).unwrap_or(Ordering::Equal))
+}
+
+/// Rotates `hull` so, that it starts at lexicographically least vertex.
+fn normalized<P: Point>(mut hull: Vec<P>) -> Outline<P> {
+    let first = (0..hull.len())
+        .min_by(|&i, &j| lex(&hull[i], &hull[j]))
+        .unwrap_or(0);
+    hull.rotate_left(first);
+    Outline::new(hull.into_iter())
+}
+
+/// Convex hull of `points` by Andrew's monotone chain algorithm in `O(n log n)` time. Hull
+/// of less than three non-collinear points has one or two vertices, or none for no points.
+pub fn convex_hull<P: Point>(points: impl IntoIterator<Item = P>) -> Outline<P> {
+    let mut points: Vec<P> = points.into_iter().collect();
+    points.sort_by(lex);
+    points.dedup();
+    if points.len() < 3 {
+        return Outline::new(points.into_iter());
+    }
+    let mut hull: Vec<P> = Vec::with_capacity(points.len() + 1);
+    // Lower chain goes forward, upper chain goes back.
+    for pass in 0..2 {
+        let start = hull.len();
+        for i in 0..points.len() {
+            let p = if pass == 0 {
+                points[i]
+            } else {
+                points[points.len() - 1 - i]
+            };
+            while hull.len() >= start + 2
+                && P::orient(hull[hull.len() - 2], hull[hull.len() - 1], p) != Ordering::Greater
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // The last point of chain starts the next one.
+        hull.pop();
+    }
+    Outline::new(hull.into_iter())
+}
+
+impl<P: Point> Outline<P> {
+    /// Convex hull of outline by Melkman's algorithm in `O(n)` time. Outline **MUST** be
+    /// simple, but may have any orientation. Use [`convex_hull`] of vertices for other
+    /// outlines.
+    pub fn convex_hull(&self) -> Outline<P> {
+        let v = self.vertices();
+        if v.len() < 3 {
+            return convex_hull(v.iter().copied());
+        }
+        let first = v[1..].iter().position(|&p| p != v[0]).map(|j| j + 1);
+        // The first vertices of simple outline lying on one line are ordered along it.
+        let third = first.and_then(|j| {
+            let k = v[j + 1..]
+                .iter()
+                .position(|&p| P::orient(v[0], v[j], p) != Ordering::Equal)?;
+            Some(j + 1 + k)
+        });
+        let k = match third {
+            Some(k) => k,
+            None => return convex_hull(v.iter().copied()),
+        };
+        let (a, b, c) = (v[0], v[k - 1], v[k]);
+        let mut deque = if P::orient(a, b, c) == Ordering::Greater {
+            VecDeque::from(vec![c, a, b, c])
+        } else {
+            VecDeque::from(vec![c, b, a, c])
+        };
+        // Deque goes counter-clockwise from bottom to top, both ends are the last vertex.
+        for &p in &v[k + 1..] {
+            let len = deque.len();
+            let left_of_top = P::orient(deque[len - 2], deque[len - 1], p) == Ordering::Greater;
+            let left_of_bottom = P::orient(deque[0], deque[1], p) == Ordering::Greater;
+            if left_of_top && left_of_bottom {
+                continue;
+            }
+            while P::orient(deque[deque.len() - 2], deque[deque.len() - 1], p) != Ordering::Greater
+            {
+                deque.pop_back();
+            }
+            deque.push_back(p);
+            while P::orient(p, deque[0], deque[1]) != Ordering::Greater {
+                deque.pop_front();
+            }
+            deque.push_front(p);
+        }
+        deque.pop_back();
+        normalized(deque.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::convex_hull;
+    use crate::location::{FillRule, Location};
+    use crate::outline::Outline;
+    use crate::testing::Random;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn point_set() {
+        let points = outline(&[
+            (1f32, 1f32),
+            (2f32, 0f32),
+            (0f32, 0f32),
+            (1f32, 0f32),
+            (2f32, 2f32),
+            (0f32, 2f32),
+            (2f32, 2f32),
+            (1f32, 2.5f32),
+        ]);
+        let expected = outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 2f32),
+            (1f32, 2.5f32),
+            (0f32, 2f32),
+        ]);
+        assert_eq!(convex_hull(points.vertices().iter().copied()), expected);
+
+        let line = convex_hull(vec![[0i32, 0], [2, 2], [1, 1], [0, 0]]);
+        assert_eq!(line.vertices(), &[[0, 0], [2, 2]]);
+        assert!(convex_hull(Vec::<Vec2>::new()).is_empty());
+    }
+
+    #[test]
+    fn simple_outline() {
+        // Clockwise outline with concave notch and collinear start.
+        let notched = outline(&[
+            (1f32, 0f32),
+            (0f32, 0f32),
+            (0f32, 2f32),
+            (1f32, 1f32),
+            (2f32, 2f32),
+            (2f32, 0f32),
+        ]);
+        let hull = notched.convex_hull();
+        let expected = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        assert_eq!(hull, expected);
+        assert!(hull.is_ccw());
+
+        let segment = outline(&[(0f32, 0f32), (1f32, 1f32), (2f32, 2f32)]);
+        assert_eq!(segment.convex_hull().len(), 2);
+        assert!(outline(&[]).convex_hull().is_empty());
+    }
+
+    #[test]
+    fn random_outlines() {
+        let mut random = Random(0x4011);
+        for _ in 0..100 {
+            // Outline with vertices sorted by angle around center is simple, if angle between
+            // neighbor vertices is less than straight.
+            let len = random.next(30) as usize;
+            let mut angles: Vec<f32> = (0..len).map(|_| random.next(1000)).collect();
+            angles.extend_from_slice(&[0f32, 333f32, 666f32]);
+            angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            angles.dedup();
+            let star = Outline::new(angles.iter().map(|&a| {
+                let angle = a / 1001f32 * std::f32::consts::PI * 2f32;
+                Vec2::new(angle.cos(), angle.sin()) * (1f32 + random.next(8))
+            }));
+            let hull = convex_hull(star.vertices().iter().copied());
+            if hull.len() < 3 {
+                continue;
+            }
+            assert_eq!(star.convex_hull(), hull);
+            assert!((0..hull.len() as isize).all(|i| hull.convex(i)));
+            for &p in star.vertices() {
+                assert_ne!(hull.locate(p, FillRule::NonZero, 0f32), Location::Outside);
+            }
+        }
+    }
+}
@@ -1,15 +1,51 @@
+pub mod boolean;
+pub mod bounds;
+pub mod calipers;
+pub mod concave;
+pub mod decomposition;
+pub mod delaunay;
+pub mod distance;
+pub mod fixed;
+mod geometry;
+pub mod hull;
+pub mod location;
+pub mod medial;
+pub mod monotone;
+pub mod offset;
 pub mod outline;
+mod overlay;
+pub mod point;
+pub mod polygon;
+pub mod prepared;
+pub mod repair;
+pub mod simplify;
+pub mod skeleton;
+pub mod sweep;
+#[cfg(test)]
+mod testing;
+pub mod triangulation;
+pub mod validation;
 
-// pub struct Polygon {
-//     outline: Outline,
-//     holes: Vec<Outline>,
-// }
-//
-// impl Polygon {
-//     pub fn new(outline: Outline, holes: Vec<Outline>) -> Self {
-//         Polygon { outline, holes }
-//     }
-// }
+pub use self::polygon::{Polygon, PolygonError};
+pub use boolean::BooleanOp;
+pub use bounds::{Aabb, Circle, OrientedRect};
+pub use calipers::{VertexPair, Width};
+pub use concave::{alpha_shape, concave_hull};
+pub use decomposition::Decomposition;
+pub use delaunay::{Mesh, Refinement};
+pub use distance::ClosestPoint;
+pub use fixed::FixedPointError;
+pub use hull::convex_hull;
+pub use location::{FillRule, Location};
+pub use medial::{MedialAxis, MedialNode};
+pub use offset::Join;
+pub use outline::{Orientation, Outline};
+pub use point::{Point, Scalar};
+pub use prepared::PreparedOutline;
+pub use repair::{Repair, RepairAction};
+pub use skeleton::{SkeletonNode, StraightSkeleton};
+pub use sweep::{EdgeRef, Intersection};
+pub use validation::{OutlineError, OutlineIssue, ValidationReport};
 
 #[cfg(test)]
 mod tests {
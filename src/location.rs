@@ -0,0 +1,306 @@
+//! Point-in-polygon queries, which tell points on boundary apart from inner and outer ones.
+//!
+//! Winding number and side tests use exact predicates, so only tolerance of boundary test
+//! is computed in floating point.
+use crate::geometry::{closest_on_segment, total_cmp};
+use crate::outline::Outline;
+use crate::point::{Point, Scalar};
+use crate::polygon::Polygon;
+use std::cmp::Ordering;
+
+/// Position of point relative to region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// Inside of region and farther than tolerance from its boundary.
+    Inside,
+    /// Outside of region and farther than tolerance from its boundary.
+    Outside,
+    /// Within tolerance from some edge.
+    OnBoundary,
+}
+
+/// Rule deciding which points are inside of self-intersecting or overlapping rings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// Point is inside, if ray from it crosses ring odd number of times.
+    EvenOdd,
+    /// Point is inside, if ring winds around it at least once in any direction.
+    NonZero,
+}
+
+impl FillRule {
+    pub(crate) fn apply(self, winding: i32) -> bool {
+        match self {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
+
+/// Edge of ring with specified index.
+type RingEdge<P> = (P, P, usize);
+
+/// Change of winding number of `p` by edge from `a` to `b`.
+pub(crate) fn winding_delta<P: Point>(a: P, b: P, p: P) -> i32 {
+    if a.y() <= p.y() {
+        if b.y() > p.y() && P::orient(a, b, p) == Ordering::Greater {
+            return 1;
+        }
+    } else if b.y() <= p.y() && P::orient(a, b, p) == Ordering::Less {
+        return -1;
+    }
+    0
+}
+
+/// Test if distance from `p` to segment from `a` to `b` doesn't exceed `tolerance`.
+pub(crate) fn near<P: Point>(a: P, b: P, p: P, tolerance: f64) -> bool {
+    let between =
+        |a: P::Scalar, b: P::Scalar, p: P::Scalar| (a <= p && p <= b) || (b <= p && p <= a);
+    if P::orient(a, b, p) == Ordering::Equal
+        && between(a.x(), b.x(), p.x())
+        && between(a.y(), b.y(), p.y())
+    {
+        return true;
+    }
+    if tolerance <= 0f64 {
+        return false;
+    }
+    let xy = |p: P| [p.x().to_f64(), p.y().to_f64()];
+    closest_on_segment(xy(a), xy(b), xy(p)).1 <= tolerance * tolerance
+}
+
+/// Location of `point` relative to region inside of the first ring and outside of others.
+fn locate<P: Point>(
+    edges: impl IntoIterator<Item = RingEdge<P>>,
+    rings: usize,
+    point: P,
+    rule: FillRule,
+    tolerance: f64,
+) -> Location {
+    let mut winding = vec![0; rings];
+    for (a, b, ring) in edges {
+        if near(a, b, point, tolerance) {
+            return Location::OnBoundary;
+        }
+        winding[ring] += winding_delta(a, b, point);
+    }
+    if rule.apply(winding[0]) && !winding[1..].iter().any(|&w| rule.apply(w)) {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Locates every point by sweeping horizontal line upward. Only edges within tolerance from
+/// line are tested, which are much fewer than all edges for typical rings.
+fn locate_all<P: Point>(
+    mut edges: Vec<RingEdge<P>>,
+    rings: usize,
+    points: &[P],
+    rule: FillRule,
+    tolerance: f64,
+) -> Vec<Location> {
+    let y = |p: P| p.y().to_f64();
+    let bottom = |&(a, b, _): &RingEdge<P>| y(a).min(y(b));
+    edges.sort_by(|s, t| total_cmp(bottom(s), bottom(t)));
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&i, &j| total_cmp(y(points[i]), y(points[j])));
+
+    let mut result = vec![Location::Outside; points.len()];
+    let mut pending = edges.into_iter().peekable();
+    let mut active = Vec::new();
+    for i in order {
+        let p = y(points[i]);
+        while let Some(edge) = pending.next_if(|edge| bottom(edge) <= p + tolerance) {
+            active.push(edge);
+        }
+        active.retain(|&(a, b, _)| y(a).max(y(b)) >= p - tolerance);
+        result[i] = locate(active.iter().copied(), rings, points[i], rule, tolerance);
+    }
+    result
+}
+
+impl<P: Point> Outline<P> {
+    /// Number of times outline winds counter-clockwise around `point`. Result for points
+    /// exactly on edges is unspecified, see [`Outline::locate`].
+    pub fn winding_number(&self, point: P) -> i32 {
+        self.edges().map(|(a, b)| winding_delta(a, b, point)).sum()
+    }
+
+    /// Location of `point` relative to outline.
+    /// # Arguments
+    /// * `rule` - decides, which points are inside of self-intersecting outline;
+    /// * `tolerance` - points not farther than this distance from some edge are on boundary.
+    ///   Zero means exactly on edge;
+    pub fn locate(&self, point: P, rule: FillRule, tolerance: P::Scalar) -> Location {
+        let edges = self.edges().map(|(a, b)| (a, b, 0));
+        locate(edges, 1, point, rule, tolerance.to_f64())
+    }
+
+    /// Locations of every point in order of `points`. Faster than [`Outline::locate`] for many
+    /// points, because points are tested only against edges close to them vertically.
+    pub fn locate_all(&self, points: &[P], rule: FillRule, tolerance: P::Scalar) -> Vec<Location> {
+        let edges = self.edges().map(|(a, b)| (a, b, 0)).collect();
+        locate_all(edges, 1, points, rule, tolerance.to_f64())
+    }
+}
+
+impl<P: Point> Polygon<P> {
+    fn ring_edges(&self) -> impl Iterator<Item = RingEdge<P>> + '_ {
+        self.rings()
+            .enumerate()
+            .flat_map(|(i, ring)| ring.edges().map(move |(a, b)| (a, b, i)))
+    }
+
+    /// Location of `point` relative to polygon. Point is inside, if it is inside of outer
+    /// outline and outside of every hole, see [`Outline::locate`].
+    pub fn locate(&self, point: P, rule: FillRule, tolerance: P::Scalar) -> Location {
+        let rings = 1 + self.holes().len();
+        locate(self.ring_edges(), rings, point, rule, tolerance.to_f64())
+    }
+
+    /// Locations of every point in order of `points`. See [`Outline::locate_all`].
+    pub fn locate_all(&self, points: &[P], rule: FillRule, tolerance: P::Scalar) -> Vec<Location> {
+        let rings = 1 + self.holes().len();
+        let edges = self.ring_edges().collect();
+        locate_all(edges, rings, points, rule, tolerance.to_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FillRule, Location};
+    use crate::outline::Outline;
+    use crate::polygon::Polygon;
+    use crate::testing::Random;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn outline_location() {
+        let square = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        let locate = |x, y, tolerance| square.locate(Vec2::new(x, y), FillRule::NonZero, tolerance);
+        assert_eq!(locate(1f32, 1f32, 0f32), Location::Inside);
+        assert_eq!(locate(3f32, 1f32, 0f32), Location::Outside);
+        assert_eq!(locate(2f32, 1f32, 0f32), Location::OnBoundary);
+        assert_eq!(locate(0f32, 0f32, 0f32), Location::OnBoundary);
+        assert_eq!(locate(1f32, 1.9f32, 0f32), Location::Inside);
+        assert_eq!(locate(1f32, 1.9f32, 0.2f32), Location::OnBoundary);
+        assert_eq!(locate(2.1f32, 2.1f32, 0.2f32), Location::OnBoundary);
+        assert_eq!(locate(2.2f32, 2.2f32, 0.2f32), Location::Outside);
+        assert_eq!(square.winding_number(Vec2::new(1f32, 1f32)), 1);
+    }
+
+    #[test]
+    fn fill_rules() {
+        // Outline goes around square twice.
+        let twice = outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 2f32),
+            (0f32, 2f32),
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 2f32),
+            (0f32, 2f32),
+        ]);
+        let center = Vec2::new(1f32, 1f32);
+        assert_eq!(twice.winding_number(center), 2);
+        assert_eq!(
+            twice.locate(center, FillRule::NonZero, 0f32),
+            Location::Inside
+        );
+        assert_eq!(
+            twice.locate(center, FillRule::EvenOdd, 0f32),
+            Location::Outside
+        );
+
+        // Lobes of figure-eight wind in opposite directions.
+        let figure_eight = outline(&[(0f32, 0f32), (2f32, 2f32), (2f32, 0f32), (0f32, 2f32)]);
+        assert_eq!(figure_eight.winding_number(Vec2::new(0.5f32, 1f32)), 1);
+        assert_eq!(figure_eight.winding_number(Vec2::new(1.5f32, 1f32)), -1);
+        assert_eq!(
+            figure_eight.locate(Vec2::new(1.5f32, 1f32), FillRule::NonZero, 0f32),
+            Location::Inside
+        );
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let outer = outline(&[(0f32, 0f32), (4f32, 0f32), (4f32, 4f32), (0f32, 4f32)]);
+        let hole = outline(&[(1f32, 1f32), (1f32, 3f32), (3f32, 3f32), (3f32, 1f32)]);
+        let polygon = Polygon::new(outer, vec![hole]).unwrap();
+        let points = [
+            Vec2::new(0.5f32, 2f32),
+            Vec2::new(2f32, 2f32),
+            Vec2::new(1f32, 2f32),
+            Vec2::new(5f32, 2f32),
+        ];
+        let expected = [
+            Location::Inside,
+            Location::Outside,
+            Location::OnBoundary,
+            Location::Outside,
+        ];
+        for (&p, &location) in points.iter().zip(expected.iter()) {
+            assert_eq!(polygon.locate(p, FillRule::EvenOdd, 0f32), location);
+        }
+        assert_eq!(
+            polygon.locate_all(&points, FillRule::EvenOdd, 0f32),
+            expected
+        );
+    }
+
+    #[test]
+    fn integer_points() {
+        let triangle = Outline::new(vec![[0i64, 0], [4, 0], [0, 4]].into_iter());
+        assert_eq!(
+            triangle.locate([2, 2], FillRule::NonZero, 0),
+            Location::OnBoundary
+        );
+        assert_eq!(
+            triangle.locate([1, 2], FillRule::NonZero, 0),
+            Location::Inside
+        );
+        assert_eq!(
+            triangle.locate([3, 2], FillRule::NonZero, 1),
+            Location::OnBoundary
+        );
+    }
+
+    #[test]
+    fn batch_matches_single() {
+        let mut random = Random(0x10ca7e);
+        for _ in 0..50 {
+            let len = 3 + random.next(10) as usize;
+            let points: Vec<_> = (0..len).map(|_| (random.next(8), random.next(8))).collect();
+            let ring = outline(&points);
+            let queries: Vec<_> = (0..100)
+                .map(|_| Vec2::new(random.next(16) * 0.5f32, random.next(16) * 0.5f32))
+                .collect();
+            for &rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+                for &tolerance in &[0f32, 0.3f32] {
+                    let single: Vec<_> = queries
+                        .iter()
+                        .map(|&p| ring.locate(p, rule, tolerance))
+                        .collect();
+                    assert_eq!(ring.locate_all(&queries, rule, tolerance), single);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nan_points() {
+        let square = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        let mut points = vec![Vec2::new(f32::NAN, 1f32); 20];
+        points.extend((0..20).map(|i| Vec2::new(1f32, if i % 2 == 0 { 1f32 } else { f32::NAN })));
+        points.push(Vec2::new(1f32, 1f32));
+        let locations = square.locate_all(&points, FillRule::NonZero, 0f32);
+        assert_eq!(locations.len(), points.len());
+        assert_eq!(locations[points.len() - 1], Location::Inside);
+    }
+}
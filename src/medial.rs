@@ -0,0 +1,264 @@
+use crate::geometry::{circumcenter, orient};
+use crate::outline::Outline;
+use glam::Vec2;
+use std::collections::{HashMap, HashSet};
+
+/// Node of medial axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MedialNode {
+    /// Position of node.
+    pub position: Vec2,
+    /// Distance from node to the closest point of outline.
+    pub radius: f32,
+}
+
+/// Approximate medial axis: graph of polylines, which meet at common nodes
+#[derive(Debug, Clone, PartialEq)]
+pub struct MedialAxis {
+    nodes: Vec<MedialNode>,
+    branches: Vec<Vec<usize>>,
+}
+
+impl MedialAxis {
+    /// Nodes of medial axis.
+    pub fn nodes(&self) -> &[MedialNode] {
+        &self.nodes
+    }
+
+    /// Polylines as sequences of indices into [`MedialAxis::nodes`]. Branches are split at
+    /// junctions, so they share only end nodes. Closed branch starts and ends with the same node.
+    pub fn branches(&self) -> &[Vec<usize>] {
+        &self.branches
+    }
+}
+
+impl Outline {
+    /// Approximates medial axis by Voronoi diagram of points sampled along outline.
+    /// # Arguments
+    /// * `spacing` - maximal distance between samples, which must be small compared to the
+    ///   narrowest part of outline. Medial axis is empty, if it isn't positive and finite;
+    /// * `significance` - Voronoi edges, whose closest samples are connected by the part of
+    ///   outline shorter than this length, are dropped. It removes noise of sampling and short
+    ///   branches, that go into convex corners.
+    pub fn medial_axis(&self, spacing: f32, significance: f32) -> MedialAxis {
+        if !(spacing > 0f32 && spacing.is_finite()) {
+            return MedialAxis {
+                nodes: Vec::new(),
+                branches: Vec::new(),
+            };
+        }
+        let (samples, along) = sample(self, spacing);
+        let perimeter = self.perimeter();
+        let sampled = Outline::new(samples.iter().copied());
+        let triangles: Vec<[usize; 3]> = sampled
+            .delaunay()
+            .into_iter()
+            .filter(|&[a, b, c]| orient(samples[a], samples[b], samples[c]) > 0f64)
+            .collect();
+
+        // Voronoi vertices are circumcenters of Delaunay triangles and Voronoi edges connect
+        // triangles sharing internal edge.
+        let mut shared: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut dual = Vec::new();
+        for (t, triangle) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                match shared.remove(&(b, a)) {
+                    Some(u) => dual.push((u, t, a, b)),
+                    None => {
+                        shared.insert((a, b), t);
+                    }
+                }
+            }
+        }
+
+        let centers: Vec<Vec2> = triangles
+            .iter()
+            .map(|&[a, b, c]| circumcenter(samples[a], samples[b], samples[c]))
+            .collect();
+        // Triangles with the same circumcenter give single node.
+        let mut parent: Vec<usize> = (0..triangles.len()).collect();
+        let epsilon = spacing * 1e-3f32;
+        for &(u, t, _, _) in &dual {
+            if (centers[u] - centers[t]).length() <= epsilon {
+                let (ru, rt) = (find(&mut parent, u), find(&mut parent, t));
+                parent[ru] = rt;
+            }
+        }
+
+        let mut kept = Vec::new();
+        for &(u, t, a, b) in &dual {
+            let gap = (along[a] - along[b]).abs();
+            if gap.min(perimeter - gap) >= significance {
+                kept.push((find(&mut parent, u), find(&mut parent, t)));
+            }
+        }
+
+        let mut index = vec![usize::MAX; triangles.len()];
+        let mut nodes = Vec::new();
+        let mut links: Vec<Vec<usize>> = Vec::new();
+        for (u, t) in kept {
+            let [m, n] = [u, t].map(|root| {
+                if index[root] == usize::MAX {
+                    index[root] = nodes.len();
+                    let position = centers[root];
+                    nodes.push(MedialNode {
+                        position,
+                        radius: distance_to_outline(self, position),
+                    });
+                    links.push(Vec::new());
+                }
+                index[root]
+            });
+            if m != n && !links[m].contains(&n) {
+                links[m].push(n);
+                links[n].push(m);
+            }
+        }
+
+        MedialAxis {
+            branches: trace_branches(&links),
+            nodes,
+        }
+    }
+}
+
+/// Points along outline not farther than `spacing` from each other and their distances along
+/// outline from the first vertex. `spacing` **MUST** be positive and finite.
+fn sample(outline: &Outline, spacing: f32) -> (Vec<Vec2>, Vec<f32>) {
+    let mut samples = Vec::new();
+    let mut along = Vec::new();
+    let mut distance = 0f32;
+    for (from, to) in outline.edges() {
+        let length = (to - from).length();
+        let count = (length / spacing).ceil().max(1f32) as usize;
+        for k in 0..count {
+            let t = k as f32 / count as f32;
+            samples.push(from + (to - from) * t);
+            along.push(distance + length * t);
+        }
+        distance += length;
+    }
+    (samples, along)
+}
+
+fn distance_to_outline(outline: &Outline, p: Vec2) -> f32 {
+    outline
+        .edges()
+        .map(|(a, b)| {
+            let ab = b - a;
+            let t = ((p - a).dot(ab) / ab.dot(ab)).clamp(0f32, 1f32);
+            (a + ab * t - p).length()
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Splits graph into polylines between nodes, which don't have exactly two links.
+fn trace_branches(links: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    let mut branches = Vec::new();
+    let walk = |start: usize, first: usize, visited: &mut HashSet<(usize, usize)>| {
+        let mut branch = vec![start];
+        let (mut from, mut to) = (start, first);
+        loop {
+            visited.insert((from, to));
+            visited.insert((to, from));
+            branch.push(to);
+            if links[to].len() != 2 || to == start {
+                break;
+            }
+            let next = if links[to][0] == from {
+                links[to][1]
+            } else {
+                links[to][0]
+            };
+            from = to;
+            to = next;
+        }
+        branch
+    };
+    let junctions = (0..links.len()).filter(|&n| links[n].len() != 2);
+    // Loops without junctions are traced in the second pass.
+    let rest = (0..links.len()).filter(|&n| links[n].len() == 2);
+    for start in junctions.chain(rest) {
+        for &first in &links[start] {
+            if !visited.contains(&(start, first)) {
+                branches.push(walk(start, first, &mut visited));
+            }
+        }
+    }
+    branches
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::outline::Outline;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn corridor() {
+        let corridor = outline(&[(0f32, 0f32), (10f32, 0f32), (10f32, 1f32), (0f32, 1f32)]);
+        let axis = corridor.medial_axis(0.1f32, 1.5f32);
+        assert_eq!(axis.branches().len(), 1);
+        let mut span = (f32::INFINITY, f32::NEG_INFINITY);
+        for node in axis.nodes() {
+            assert!((node.position.y() - 0.5f32).abs() < 1e-3f32);
+            assert!((node.radius - 0.5f32).abs() < 1e-3f32);
+            span = (span.0.min(node.position.x()), span.1.max(node.position.x()));
+        }
+        assert!(span.0 < 1f32 && span.1 > 9f32);
+    }
+
+    #[test]
+    fn invalid_spacing() {
+        let square = outline(&[(0f32, 0f32), (1f32, 0f32), (1f32, 1f32), (0f32, 1f32)]);
+        for &spacing in &[0f32, -1f32, f32::NAN, f32::INFINITY] {
+            let axis = square.medial_axis(spacing, 0f32);
+            assert!(axis.nodes().is_empty());
+            assert!(axis.branches().is_empty());
+        }
+    }
+
+    #[test]
+    fn junction() {
+        // T-shaped corridors of unit width.
+        let tee = outline(&[
+            (0f32, 0f32),
+            (9f32, 0f32),
+            (9f32, 1f32),
+            (5f32, 1f32),
+            (5f32, 6f32),
+            (4f32, 6f32),
+            (4f32, 1f32),
+            (0f32, 1f32),
+        ]);
+        let axis = tee.medial_axis(0.05f32, 2f32);
+        assert_eq!(axis.branches().len(), 3);
+        // Junction is equidistant from the bottom wall and both inner corners.
+        let ends = |b: &Vec<usize>| [b[0], *b.last().unwrap()];
+        let junction = ends(&axis.branches()[0])
+            .iter()
+            .copied()
+            .find(|n| axis.branches().iter().all(|b| ends(b).contains(n)))
+            .unwrap();
+        let node = axis.nodes()[junction];
+        assert!((node.position - Vec2::new(4.5f32, 0.625f32)).length() < 1e-2f32);
+        assert!((node.radius - 0.625f32).abs() < 1e-2f32);
+        for node in axis.nodes() {
+            assert!(tee.contains(node.position));
+            assert!(node.radius <= 0.625f32 + 1e-2f32);
+        }
+    }
+}
@@ -0,0 +1,533 @@
+use crate::geometry::cmp_turn;
+use crate::outline::Outline;
+use crate::point::Point;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+impl<P: Point> Outline<P> {
+    /// Partitions outline into y-monotone pieces with sweep line, in `O(n log n)` time.
+    /// Returns pieces as counter-clockwise rings of vertex indices. Every horizontal line
+    /// crosses boundary of each piece at most twice.
+    pub fn monotone_pieces(&self) -> Vec<Vec<usize>> {
+        if self.len() < 3 {
+            return Vec::new();
+        }
+        let diagonals = MonotoneSweep::new(self).run();
+        split_by_diagonals(self, &diagonals)
+    }
+
+    /// Triangulates outline by partition into y-monotone pieces and linear time triangulation
+    /// of each piece. Suits large outlines much better than [`Outline::triangulate`].
+    /// Returns counter-clockwise triangles as triples of vertex indices.
+    pub fn triangulate_monotone(&self) -> Vec<[usize; 3]> {
+        let mut triangles = Vec::with_capacity(self.len().saturating_sub(2));
+        for piece in self.monotone_pieces() {
+            triangulate_piece(self.vertices(), &piece, &mut triangles);
+        }
+        triangles
+    }
+}
+
+/// Sweep order: from top to bottom, from left to right on same height.
+fn above<P: Point>(a: P, b: P) -> bool {
+    a.y() > b.y() || (a.y() == b.y() && a.x() < b.x())
+}
+
+fn cmp_sweep<P: Point>(a: P, b: P) -> Ordering {
+    if above(a, b) {
+        Ordering::Less
+    } else if above(b, a) {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VertexKind {
+    Start,
+    Split,
+    End,
+    Merge,
+    Regular,
+}
+
+/// Edge from `upper` to `lower` end in sweep order, or probe point with both ends equal.
+#[derive(Debug, Clone, Copy)]
+struct Active<P> {
+    upper: P,
+    lower: P,
+    edge: Option<usize>,
+}
+
+impl<P: Point> Active<P> {
+    fn probe(p: P) -> Self {
+        Active {
+            upper: p,
+            lower: p,
+            edge: None,
+        }
+    }
+
+    /// Test if edge is at the left of `p`, which lies on sweep line. Horizontal edge is at the
+    /// left of points on it after its upper end.
+    fn left_of(&self, p: P) -> bool {
+        match P::orient(self.upper, self.lower, p) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => self.upper.y() == self.lower.y() && self.upper.x() < p.x(),
+        }
+    }
+}
+
+impl<P: Point> PartialEq for Active<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Point> Eq for Active<P> {}
+
+impl<P: Point> PartialOrd for Active<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Point> Ord for Active<P> {
+    /// Left to right order of edges, which both span sweep line and don't cross.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = match (self.edge, other.edge) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) if self.left_of(other.upper) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return other.cmp(self).reverse(),
+            (Some(a), Some(b)) => (a, b),
+        };
+        if a == b {
+            return Ordering::Equal;
+        }
+        // Edge, which starts later, is compared with the other one at its upper end.
+        match cmp_sweep(self.upper, other.upper) {
+            Ordering::Less if self.left_of(other.upper) => Ordering::Less,
+            Ordering::Less => Ordering::Greater,
+            Ordering::Greater => other.cmp(self).reverse(),
+            Ordering::Equal => P::orient(self.upper, self.lower, other.lower)
+                .reverse()
+                .then(a.cmp(&b)),
+        }
+    }
+}
+
+/// Sweep line state of monotone partition. Edge `i` goes from vertex `i` to vertex `i+1`.
+struct MonotoneSweep<'a, P> {
+    outline: &'a Outline<P>,
+    /// Edges crossing sweep line with inner area at their right, sorted from left to right.
+    status: BTreeSet<Active<P>>,
+    helper: Vec<usize>,
+    diagonals: Vec<(usize, usize)>,
+}
+
+impl<'a, P: Point> MonotoneSweep<'a, P> {
+    fn new(outline: &'a Outline<P>) -> Self {
+        MonotoneSweep {
+            outline,
+            status: BTreeSet::new(),
+            helper: vec![0; outline.len()],
+            diagonals: Vec::new(),
+        }
+    }
+
+    fn vertex(&self, i: usize) -> P {
+        self.outline[i as isize]
+    }
+
+    fn prev(&self, i: usize) -> usize {
+        (i + self.outline.len() - 1) % self.outline.len()
+    }
+
+    fn next(&self, i: usize) -> usize {
+        (i + 1) % self.outline.len()
+    }
+
+    fn kind(&self, i: usize) -> VertexKind {
+        let (prev, that, next) = (
+            self.vertex(self.prev(i)),
+            self.vertex(i),
+            self.vertex(self.next(i)),
+        );
+        let convex = P::orient(prev, that, next) == Ordering::Greater;
+        match (above(that, prev), above(that, next), convex) {
+            (true, true, true) => VertexKind::Start,
+            (true, true, false) => VertexKind::Split,
+            (false, false, true) => VertexKind::End,
+            (false, false, false) => VertexKind::Merge,
+            _ => VertexKind::Regular,
+        }
+    }
+
+    fn active(&self, edge: usize) -> Active<P> {
+        let (a, b) = (self.vertex(edge), self.vertex(self.next(edge)));
+        let (upper, lower) = if above(a, b) { (a, b) } else { (b, a) };
+        Active {
+            upper,
+            lower,
+            edge: Some(edge),
+        }
+    }
+
+    fn insert(&mut self, edge: usize, helper: usize) {
+        let active = self.active(edge);
+        self.status.insert(active);
+        self.helper[edge] = helper;
+    }
+
+    /// Removes edge, which ends at sweep position.
+    fn remove(&mut self, edge: usize) {
+        let active = self.active(edge);
+        if !self.status.remove(&active) {
+            // Order of status is consistent only for edges of simple outline.
+            self.status.retain(|other| other.edge != Some(edge));
+        }
+    }
+
+    /// Edge of status directly at the left of vertex `i`.
+    fn left_of(&self, i: usize) -> Option<usize> {
+        let probe = Active::probe(self.vertex(i));
+        self.status.range(..probe).next_back()?.edge
+    }
+
+    fn connect_merge_helper(&mut self, i: usize, edge: usize) {
+        let helper = self.helper[edge];
+        if self.kind(helper) == VertexKind::Merge {
+            self.diagonals.push((i, helper));
+        }
+    }
+
+    fn run(mut self) -> Vec<(usize, usize)> {
+        let mut order: Vec<usize> = (0..self.outline.len()).collect();
+        order.sort_by(|&a, &b| cmp_sweep(self.vertex(a), self.vertex(b)));
+        for i in order {
+            let prev_edge = self.prev(i);
+            match self.kind(i) {
+                VertexKind::Start => self.insert(i, i),
+                VertexKind::End => {
+                    self.connect_merge_helper(i, prev_edge);
+                    self.remove(prev_edge);
+                }
+                VertexKind::Split => {
+                    if let Some(left) = self.left_of(i) {
+                        self.diagonals.push((i, self.helper[left]));
+                        self.helper[left] = i;
+                    }
+                    self.insert(i, i);
+                }
+                VertexKind::Merge => {
+                    self.connect_merge_helper(i, prev_edge);
+                    self.remove(prev_edge);
+                    if let Some(left) = self.left_of(i) {
+                        self.connect_merge_helper(i, left);
+                        self.helper[left] = i;
+                    }
+                }
+                VertexKind::Regular => {
+                    // Boundary goes downward, so inner area is at the right of vertex.
+                    if above(self.vertex(prev_edge), self.vertex(i)) {
+                        self.connect_merge_helper(i, prev_edge);
+                        self.remove(prev_edge);
+                        self.insert(i, i);
+                    } else if let Some(left) = self.left_of(i) {
+                        self.connect_merge_helper(i, left);
+                        self.helper[left] = i;
+                    }
+                }
+            }
+        }
+        self.diagonals
+    }
+}
+
+/// Splits outline into faces bounded by its edges and `diagonals`.
+fn split_by_diagonals<P: Point>(
+    outline: &Outline<P>,
+    diagonals: &[(usize, usize)],
+) -> Vec<Vec<usize>> {
+    let len = outline.len();
+    // Outgoing half-edges of every vertex: next vertex of outline, then diagonals.
+    let mut outgoing: Vec<Vec<usize>> = (0..len).map(|i| vec![(i + 1) % len]).collect();
+    for &(a, b) in diagonals {
+        outgoing[a].push(b);
+        outgoing[b].push(a);
+    }
+    let mut visited: Vec<Vec<bool>> = outgoing.iter().map(|out| vec![false; out.len()]).collect();
+
+    let mut pieces = Vec::new();
+    for start in 0..len {
+        for k in 0..outgoing[start].len() {
+            if visited[start][k] {
+                continue;
+            }
+            let mut piece = Vec::new();
+            let (mut from, mut slot) = (start, k);
+            while !visited[from][slot] {
+                visited[from][slot] = true;
+                piece.push(from);
+                let to = outgoing[from][slot];
+                // Inner area is at the left, so take the first half-edge clockwise from the
+                // reversed incoming one.
+                let point = |i: usize| outline[i as isize];
+                slot = (0..outgoing[to].len())
+                    .min_by(|&x, &y| {
+                        let (p, q) = (point(outgoing[to][x]), point(outgoing[to][y]));
+                        cmp_turn(point(to), point(from), p, q, true)
+                    })
+                    .unwrap();
+                from = to;
+            }
+            pieces.push(piece);
+        }
+    }
+    pieces
+}
+
+/// Triangulates y-monotone counter-clockwise `piece` with stack of reflex chain vertices.
+fn triangulate_piece<P: Point>(points: &[P], piece: &[usize], triangles: &mut Vec<[usize; 3]>) {
+    let len = piece.len();
+    if len < 3 {
+        return;
+    }
+    let point = |pos: usize| points[piece[pos]];
+    let top = (0..len)
+        .min_by(|&a, &b| cmp_sweep(point(a), point(b)))
+        .unwrap();
+    let bottom = (0..len)
+        .max_by(|&a, &b| cmp_sweep(point(a), point(b)))
+        .unwrap();
+
+    // Left chain goes down from top in ring order, right chain goes up to top.
+    let left: Vec<usize> = (0..len)
+        .map(|k| (top + k) % len)
+        .take_while(|&pos| pos != bottom)
+        .collect();
+    let right: Vec<usize> = (0..len)
+        .map(|k| (top + len - k) % len)
+        .skip(1)
+        .take_while(|&pos| pos != bottom)
+        .collect();
+    let mut sorted = Vec::with_capacity(len);
+    let (mut l, mut r) = (0, 0);
+    while l < left.len() || r < right.len() {
+        let take_left =
+            r >= right.len() || (l < left.len() && above(point(left[l]), point(right[r])));
+        if take_left {
+            sorted.push((left[l], true));
+            l += 1;
+        } else {
+            sorted.push((right[r], false));
+            r += 1;
+        }
+    }
+    sorted.push((bottom, true));
+
+    // Triangle of `apex` with consecutive `upper` and `lower` vertices of stack, which lies on
+    // left or right chain, is counter-clockwise in this order.
+    let mut emit = |apex: usize, upper: usize, lower: usize, left: bool| {
+        let triangle = if left {
+            [apex, upper, lower]
+        } else {
+            [apex, lower, upper]
+        };
+        let [a, b, c] = triangle;
+        debug_assert_ne!(
+            P::orient(point(a), point(b), point(c)),
+            Ordering::Less,
+            "monotone chains must produce counter-clockwise triangles"
+        );
+        triangles.push([piece[a], piece[b], piece[c]]);
+    };
+
+    let mut stack = vec![sorted[0], sorted[1]];
+    for &(pos, is_left) in &sorted[2..len - 1] {
+        let top = *stack.last().unwrap();
+        if top.1 != is_left {
+            for pair in stack.windows(2) {
+                emit(pos, pair[0].0, pair[1].0, top.1);
+            }
+            stack = vec![top, (pos, is_left)];
+        } else {
+            let mut last = stack.pop().unwrap();
+            while let Some(&next) = stack.last() {
+                let inside = if is_left {
+                    P::orient(point(next.0), point(last.0), point(pos)) == Ordering::Greater
+                } else {
+                    P::orient(point(pos), point(last.0), point(next.0)) == Ordering::Greater
+                };
+                if !inside {
+                    break;
+                }
+                emit(pos, next.0, last.0, is_left);
+                last = stack.pop().unwrap();
+            }
+            stack.push(last);
+            stack.push((pos, is_left));
+        }
+    }
+    let left = stack.last().unwrap().1;
+    for pair in stack.windows(2) {
+        emit(bottom, pair[0].0, pair[1].0, left);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::above;
+    use crate::geometry::orient;
+    use crate::outline::Outline;
+    use crate::point::Point;
+    use crate::testing::Random;
+    use glam::Vec2;
+    use std::cmp::Ordering;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    /// Star-shaped outline with pseudo-random radii.
+    fn star(count: usize, seed: u64) -> Outline {
+        let mut random = Random(seed);
+        Outline::new((0..count).map(|i| {
+            let radius = 1f32 + random.next(999) / 100f32;
+            let angle = i as f32 / count as f32 * 2f32 * std::f32::consts::PI;
+            Vec2::new(angle.cos(), angle.sin()) * radius
+        }))
+    }
+
+    fn area(points: &[Vec2], triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|t| orient(points[t[0]], points[t[1]], points[t[2]]) as f32 * 0.5f32)
+            .sum()
+    }
+
+    fn assert_monotone(outline: &Outline, piece: &[usize]) {
+        let len = piece.len();
+        let local_max = (0..len)
+            .filter(|&k| {
+                let that = outline[piece[k] as isize];
+                let prev = outline[piece[(k + len - 1) % len] as isize];
+                let next = outline[piece[(k + 1) % len] as isize];
+                above(that, prev) && above(that, next)
+            })
+            .count();
+        assert_eq!(local_max, 1);
+    }
+
+    fn assert_triangulation(outline: &Outline, triangles: &[[usize; 3]]) {
+        let points = outline.vertices();
+        assert_eq!(triangles.len(), outline.len() - 2);
+        for t in triangles {
+            assert!(orient(points[t[0]], points[t[1]], points[t[2]]) > 0f64);
+        }
+        let expected = outline.signed_area();
+        assert!((area(points, triangles) - expected).abs() <= expected * 1e-4f32);
+    }
+
+    #[test]
+    fn pieces() {
+        // Split vertex at the bottom, merge vertex at the top.
+        let outline = outline(&[
+            (0f32, 0f32),
+            (2f32, 1f32),
+            (4f32, 0f32),
+            (4f32, 4f32),
+            (2f32, 3f32),
+            (0f32, 4f32),
+        ]);
+        let pieces = outline.monotone_pieces();
+        assert_eq!(pieces.len(), 2);
+        let mut total = 0f32;
+        for piece in &pieces {
+            assert_monotone(&outline, piece);
+            let ring = Outline::new(piece.iter().map(|&i| outline[i as isize]));
+            assert!(ring.signed_area() > 0f32);
+            total += ring.signed_area();
+        }
+        assert_eq!(total, outline.signed_area());
+    }
+
+    #[test]
+    fn monotone_outline_is_single_piece() {
+        let outline = outline(&[(0f32, 0f32), (3f32, 1f32), (1f32, 2f32), (2f32, 3f32)]);
+        assert_eq!(outline.monotone_pieces(), vec![vec![0, 1, 2, 3]]);
+    }
+
+    #[test]
+    fn triangulate() {
+        let outline = outline(&[
+            (0f32, 0f32),
+            (2f32, 1f32),
+            (4f32, 0f32),
+            (3f32, 2f32),
+            (4f32, 4f32),
+            (2f32, 3f32),
+            (0f32, 4f32),
+            (1f32, 2f32),
+        ]);
+        assert_triangulation(&outline, &outline.triangulate_monotone());
+    }
+
+    #[test]
+    fn collinear_vertices() {
+        let outline = outline(&[
+            (0f32, 0f32),
+            (1f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 1f32),
+            (2f32, 2f32),
+            (1f32, 3f32),
+            (0f32, 4f32),
+            (0f32, 2f32),
+        ]);
+        assert_triangulation(&outline, &outline.triangulate_monotone());
+    }
+
+    #[test]
+    fn large_star() {
+        let outline = star(20000, 7);
+        let pieces = outline.monotone_pieces();
+        for piece in &pieces {
+            assert_monotone(&outline, piece);
+        }
+        assert_triangulation(&outline, &outline.triangulate_monotone());
+    }
+
+    #[test]
+    fn integer_points() {
+        // Offset beyond `f32` precision doesn't affect integer outlines.
+        let base = 1i64 << 40;
+        let points = [
+            (0, 0),
+            (2, 1),
+            (4, 0),
+            (3, 2),
+            (4, 4),
+            (2, 3),
+            (0, 4),
+            (1, 2),
+        ];
+        let outline = Outline::new(points.iter().map(|&(x, y)| [base + x, base + y]));
+        let float = Outline::new(points.iter().map(|&(x, y)| Vec2::new(x as f32, y as f32)));
+        assert_eq!(outline.monotone_pieces(), float.monotone_pieces());
+        let triangles = outline.triangulate_monotone();
+        assert_eq!(triangles.len(), 6);
+        let area: i128 = triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| outline[i as isize]);
+                assert_eq!(Point::orient(a, b, c), Ordering::Greater);
+                Outline::new([a, b, c].iter().copied()).doubled_signed_area()
+            })
+            .sum();
+        assert_eq!(area, outline.doubled_signed_area());
+    }
+}
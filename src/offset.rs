@@ -0,0 +1,306 @@
+use crate::geometry::orient;
+use crate::outline::Outline;
+use crate::overlay::{overlay, Segment};
+use crate::polygon::Polygon;
+use glam::Vec2;
+use std::f32::consts::PI;
+
+/// Shape of offset outline at vertices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Join {
+    /// Offset edges are extended until they meet. If the meeting point is farther than
+    /// `limit` multiplied by offset distance, corner is squared.
+    Miter { limit: f32 },
+    /// Corner is rounded by arc, which deviates from true circle at most by `tolerance`.
+    Round { tolerance: f32 },
+    /// Corner is cut perpendicular to its bisector at offset distance from vertex.
+    Square,
+}
+
+impl Outline {
+    /// Offsets outline by `distance`: positive value inflates outline, negative deflates it.
+    /// Self-intersections are resolved, so result may consist of several polygons with holes.
+    /// # Arguments
+    /// * `distance` - signed offset distance;
+    /// * `join` - shape of corners, that appear at convex vertices when inflating and at
+    ///   concave vertices when deflating;
+    pub fn offset(&self, distance: f32, join: Join) -> Vec<Polygon> {
+        let mut segments = Vec::new();
+        offset_ring(self, distance, join, &mut segments);
+        overlay(&segments, |winding| winding[0] > 0)
+    }
+}
+
+impl Polygon {
+    /// Offsets polygon with holes by `distance`. See [`Outline::offset`].
+    pub fn offset(&self, distance: f32, join: Join) -> Vec<Polygon> {
+        let mut segments = Vec::new();
+        for ring in self.rings() {
+            offset_ring(ring, distance, join, &mut segments);
+        }
+        overlay(&segments, |winding| winding[0] > 0)
+    }
+}
+
+/// Normal at the right side of direction, which looks outside of inner area.
+fn outer_normal(dir: Vec2) -> Vec2 {
+    Vec2::new(dir.y(), -dir.x())
+}
+
+/// Appends edges of raw offset ring, which may intersect itself, to `segments`.
+fn offset_ring(ring: &Outline, distance: f32, join: Join, segments: &mut Vec<Segment>) {
+    // Coincident vertices are merged, so every edge has direction.
+    let vertices = ring.vertices();
+    let ring = Outline::new(
+        (0..vertices.len())
+            .filter(|&i| vertices[i] != vertices[(i + 1) % vertices.len()])
+            .map(|i| vertices[i]),
+    );
+    let len = ring.len() as isize;
+    let mut points = Vec::with_capacity(ring.len() * 2);
+    for i in 0..len {
+        let (prev, that, next) = ring.prev_that_next(i);
+        let corner = Corner {
+            that,
+            dir_prev: (that - prev).normalize(),
+            dir_next: (next - that).normalize(),
+            distance,
+        };
+        let start = corner.start();
+        let end = corner.end();
+        let turn = orient(prev, that, next);
+        // Corner opens a gap at convex vertex when inflating and at concave when deflating.
+        let opens = (distance > 0f32 && turn > 0f64) || (distance < 0f32 && turn < 0f64);
+        points.push(start);
+        if opens {
+            // Angle between offset edges, that must be filled by join.
+            let sweep = if distance > 0f32 {
+                ring.outer_angle(i) - PI
+            } else {
+                ring.inner_angle(i) + PI
+            };
+            corner.join(sweep, join, &mut points);
+        } else if start != end {
+            // Overlapping offset edges are connected through vertex, so loop appears, which is
+            // removed when self-intersections are resolved.
+            points.push(that);
+        }
+        points.push(end);
+    }
+    let count = points.len();
+    for k in 0..count {
+        segments.push((points[k], points[(k + 1) % count], 0));
+    }
+}
+
+/// Vertex with unit directions of adjacent edges.
+struct Corner {
+    that: Vec2,
+    dir_prev: Vec2,
+    dir_next: Vec2,
+    distance: f32,
+}
+
+impl Corner {
+    /// End of offset previous edge.
+    fn start(&self) -> Vec2 {
+        self.that + outer_normal(self.dir_prev) * self.distance
+    }
+
+    /// Start of offset next edge.
+    fn end(&self) -> Vec2 {
+        self.that + outer_normal(self.dir_next) * self.distance
+    }
+
+    /// Pushes points between [`Corner::start`] and [`Corner::end`].
+    fn join(&self, sweep: f32, join: Join, points: &mut Vec<Vec2>) {
+        let radius = self.distance.abs();
+        let normals = outer_normal(self.dir_prev) + outer_normal(self.dir_next);
+        let bisector = normals.normalize() * self.distance.signum();
+        // Cosine of angle between normals equals to `cos(sweep)`.
+        let normals_cos = outer_normal(self.dir_prev).dot(outer_normal(self.dir_next));
+        let miter = normals * (self.distance / (1f32 + normals_cos));
+        match join {
+            Join::Miter { limit } if normals_cos > -1f32 && miter.length() <= limit * radius => {
+                points.push(self.that + miter);
+            }
+            Join::Round { tolerance } => {
+                let tolerance = tolerance.max(radius * 1e-4f32).min(radius);
+                let step = 2f32 * (1f32 - tolerance / radius).acos();
+                let steps = (sweep / step).ceil().max(1f32) as usize;
+                // Normals turn counter-clockwise at convex vertices and clockwise at concave.
+                let angle = sweep * self.distance.signum() / steps as f32;
+                let (sin, cos) = angle.sin_cos();
+                let mut offset = self.start() - self.that;
+                for _ in 1..steps {
+                    offset = Vec2::new(
+                        offset.x() * cos - offset.y() * sin,
+                        offset.x() * sin + offset.y() * cos,
+                    );
+                    points.push(self.that + offset);
+                }
+            }
+            _ => {
+                // Both offset edges are cut by line at `radius` from vertex along bisector.
+                let cut = |from: Vec2, dir: Vec2| {
+                    let along = dir.dot(bisector);
+                    if along.abs() <= f32::EPSILON {
+                        return from;
+                    }
+                    from + dir * ((radius - (from - self.that).dot(bisector)) / along)
+                };
+                points.push(cut(self.start(), self.dir_prev));
+                points.push(cut(self.end(), self.dir_next));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Join;
+    use crate::outline::Outline;
+    use crate::polygon::Polygon;
+    use glam::Vec2;
+    use std::f32::consts::PI;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    fn square(size: f32) -> Outline {
+        outline(&[(0f32, 0f32), (size, 0f32), (size, size), (0f32, size)])
+    }
+
+    fn area(polygons: &[Polygon]) -> f32 {
+        polygons.iter().map(Polygon::area).sum()
+    }
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn inflate_square() {
+        let square = square(2f32);
+        let miter = square.offset(1f32, Join::Miter { limit: 2f32 });
+        assert_eq!(miter.len(), 1);
+        assert_eq!(miter[0].outline().len(), 4);
+        assert_close(area(&miter), 16f32, 1e-4f32);
+
+        // Miter of right angle is longer than `limit`, so corners are squared.
+        let limited = square.offset(1f32, Join::Miter { limit: 1.2f32 });
+        let cut = (2f32.sqrt() - 1f32).powi(2);
+        assert_close(area(&limited), 16f32 - 4f32 * cut, 1e-4f32);
+        assert_close(
+            area(&square.offset(1f32, Join::Square)),
+            16f32 - 4f32 * cut,
+            1e-4f32,
+        );
+
+        let round = square.offset(1f32, Join::Round { tolerance: 1e-3f32 });
+        assert_close(area(&round), 4f32 + 8f32 + PI, 1e-2f32);
+    }
+
+    #[test]
+    fn duplicate_vertex() {
+        let doubled = outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 2f32),
+            (0f32, 2f32),
+            (0f32, 0f32),
+        ]);
+        let result = doubled.offset(0.5f32, Join::Miter { limit: 2f32 });
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].outline().len(), 4);
+        assert_close(area(&result), 9f32, 1e-4f32);
+    }
+
+    #[test]
+    fn deflate_square() {
+        let square = square(2f32);
+        for &join in &[
+            Join::Miter { limit: 2f32 },
+            Join::Round { tolerance: 0.1f32 },
+        ] {
+            let result = square.offset(-0.5f32, join);
+            assert_eq!(result.len(), 1);
+            assert_close(area(&result), 1f32, 1e-4f32);
+            assert!(square.offset(-1.5f32, join).is_empty());
+        }
+    }
+
+    #[test]
+    fn deflate_splits() {
+        // Two rooms connected by narrow corridor.
+        let dumbbell = outline(&[
+            (0f32, 0f32),
+            (3f32, 0f32),
+            (3f32, 1f32),
+            (5f32, 1f32),
+            (5f32, 0f32),
+            (8f32, 0f32),
+            (8f32, 3f32),
+            (5f32, 3f32),
+            (5f32, 1.5f32),
+            (3f32, 1.5f32),
+            (3f32, 3f32),
+            (0f32, 3f32),
+        ]);
+        let result = dumbbell.offset(-0.5f32, Join::Miter { limit: 2f32 });
+        assert_eq!(result.len(), 2);
+        assert_close(area(&result), 8f32, 1e-4f32);
+    }
+
+    #[test]
+    fn inflate_closes_hole() {
+        // C shape with narrow gap turns into ring.
+        let c_shape = outline(&[
+            (0f32, 0f32),
+            (4f32, 0f32),
+            (4f32, 1.8f32),
+            (3f32, 1.8f32),
+            (3f32, 1f32),
+            (1f32, 1f32),
+            (1f32, 3f32),
+            (3f32, 3f32),
+            (3f32, 2.2f32),
+            (4f32, 2.2f32),
+            (4f32, 4f32),
+            (0f32, 4f32),
+        ]);
+        let result = c_shape.offset(0.25f32, Join::Miter { limit: 2f32 });
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].holes().len(), 1);
+        assert_close(
+            result[0].holes()[0].signed_area(),
+            -1.5f32 * 1.5f32,
+            1e-4f32,
+        );
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let hole = Outline::new(
+            square(2f32)
+                .vertices()
+                .iter()
+                .rev()
+                .map(|&v| v + Vec2::new(1f32, 1f32)),
+        );
+        let polygon = Polygon::new(square(4f32), vec![hole]).unwrap();
+        let result = polygon.offset(0.5f32, Join::Miter { limit: 2f32 });
+        assert_eq!(result.len(), 1);
+        assert_close(result[0].area(), 25f32 - 1f32, 1e-4f32);
+        assert!(polygon.offset(1f32, Join::Miter { limit: 2f32 })[0]
+            .holes()
+            .is_empty());
+    }
+}
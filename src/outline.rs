@@ -1,46 +1,181 @@
+use crate::point::{Point, Scalar};
 use glam::Vec2;
+use std::cmp::Ordering;
 use std::ops::Index;
 
-/// Represent closed circuit of vertices
-pub struct Outline {
-    vertices: Vec<Vec2>,
+/// Direction in which outline vertices go around enclosed region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Inner area is at left side of edges, as [`Outline::new`] requires.
+    CounterClockwise,
+    /// Inner area is at right side of edges.
+    Clockwise,
+    /// Enclosed region has zero signed area.
+    Degenerate,
 }
 
-impl Outline {
+/// Represent closed circuit of vertices. Vertices are [`glam::Vec2`] by default, any other
+/// [`Point`] like `[f64; 2]` or `[i64; 2]` may be used for better precision.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outline<P = Vec2> {
+    vertices: Vec<P>,
+}
+
+impl<P: Point> Outline<P> {
     /// Creates new outline.
     /// # Arguments
     /// * `vertices` - iterator of vertices. They **MUST** follow in order, which guarantee:
     /// 1) when follow from i to i+1 vertex, inner area of polygon **MUST** be at left side;
-    pub fn new(vertices: impl Iterator<Item = Vec2>) -> Self {
+    pub fn new(vertices: impl Iterator<Item = P>) -> Self {
         Outline {
             vertices: vertices.collect(),
         }
     }
 
+    /// Number of vertices in outline.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Test if outline has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Slice of outline vertices in their order.
+    pub fn vertices(&self) -> &[P] {
+        &self.vertices
+    }
+
+    /// Iterator over edges as (`from`, `to`) pairs. Last edge connects last vertex with first.
+    pub fn edges(&self) -> impl Iterator<Item = (P, P)> + '_ {
+        let count = self.vertices.len() as isize;
+        (0..count).map(move |i| (self[i], self[i + 1]))
+    }
+
+    /// Edge as (`from`, `to`) pair. Edge `i` goes from `i`-th to `i+1`-th vertex;
+    /// * `i` - index of edge. May be negative;
+    pub fn edge(&self, i: isize) -> (P, P) {
+        (self[i], self[i + 1])
+    }
+
+    /// Outline with every vertex converted by `f`.
+    pub fn map<Q: Point>(&self, f: impl FnMut(P) -> Q) -> Outline<Q> {
+        Outline::new(self.vertices.iter().copied().map(f))
+    }
+
+    /// Doubled signed area of enclosed region. Exact for integer points.
+    pub fn doubled_signed_area(&self) -> P::Area {
+        self.edges()
+            .fold(P::Area::zero(), |sum, (from, to)| sum + from.cross(to))
+    }
+
+    /// Orientation by sign of [`Outline::doubled_signed_area`]. Exact for integer points.
+    pub fn orientation(&self) -> Orientation {
+        match self.doubled_signed_area().partial_cmp(&P::Area::zero()) {
+            Some(Ordering::Greater) => Orientation::CounterClockwise,
+            Some(Ordering::Less) => Orientation::Clockwise,
+            _ => Orientation::Degenerate,
+        }
+    }
+
+    /// Test if outline is counter-clockwise, as [`Outline::new`] requires.
+    pub fn is_ccw(&self) -> bool {
+        self.orientation() == Orientation::CounterClockwise
+    }
+
+    /// Reverses order of vertices, so orientation is flipped.
+    pub fn reverse(&mut self) {
+        self.vertices.reverse();
+    }
+
+    /// Reverses clockwise outline, so it follows [`Outline::new`] orientation contract.
+    /// Returns `true` if outline was reversed.
+    pub fn ensure_ccw(&mut self) -> bool {
+        let clockwise = self.orientation() == Orientation::Clockwise;
+        if clockwise {
+            self.reverse();
+        }
+        clockwise
+    }
+
+    /// Test if `point` is inside outline using crossing number rule.
+    /// Result for points exactly on edges is unspecified, see [`Outline::locate`].
+    pub fn contains(&self, point: P) -> bool {
+        let mut inside = false;
+        for (from, to) in self.edges() {
+            let upward = to.y() > point.y();
+            if (from.y() > point.y()) != upward {
+                // Ray to the right crosses edge, if point is at the left of upward edge.
+                let side = P::orient(from, to, point);
+                if side != Ordering::Equal && (side == Ordering::Greater) == upward {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
     /// Tuple of (`i-1`, `i`, `i+1`) vertices;
     /// * `i` - index of vertex. May be negative;
-    pub fn prev_that_next(&self, i: isize) -> (Vec2, Vec2, Vec2) {
+    pub fn prev_that_next(&self, i: isize) -> (P, P, P) {
         (self[i - 1], self[i], self[i + 1])
     }
 
-    /// Tuple of vectors to previous and to next vertex for `i`-th vertex;
+    /// Test if angle is convex. Decision is exact, so vertices on straight line and vertices
+    /// with coincident neighbors aren't convex;
     /// * `i` - index of vertex. May be negative;
-    pub fn to_neighbors(&self, i: isize) -> (Vec2, Vec2) {
+    pub fn convex(&self, i: isize) -> bool {
         let (prev, that, next) = self.prev_that_next(i);
-        (prev - that, next - that)
+        P::orient(prev, that, next) == Ordering::Greater
     }
 
-    /// Test if angle is convex;
+    /// Test if angle is concave, i.e. not convex;
     /// * `i` - index of vertex. May be negative;
-    pub fn convex(&self, i: isize) -> bool {
-        let (_, sin) = self.inner_angle_cos_sin(i);
-        return sin > 0f32;
+    pub fn concave(&self, i: isize) -> bool {
+        !self.convex(i)
     }
+}
 
-    /// Test if angle is concave;
+impl Outline {
+    /// Signed area of enclosed region. Positive for counter-clockwise outline, negative for
+    /// clockwise.
+    pub fn signed_area(&self) -> f32 {
+        self.doubled_signed_area() * 0.5f32
+    }
+
+    /// Total length of outline edges.
+    pub fn perimeter(&self) -> f32 {
+        self.edges().map(|(from, to)| (to - from).length()).sum()
+    }
+
+    /// Center of mass of enclosed region. `None` if area is zero.
+    pub fn centroid(&self) -> Option<Vec2> {
+        let origin = *self.vertices.first()?;
+        let relative = |p: Vec2| {
+            let p = p - origin;
+            (f64::from(p.x()), f64::from(p.y()))
+        };
+        let (mut area, mut x, mut y) = (0f64, 0f64, 0f64);
+        for (from, to) in self.edges() {
+            let (from, to) = (relative(from), relative(to));
+            let cross = from.0 * to.1 - from.1 * to.0;
+            area += cross;
+            x += (from.0 + to.0) * cross;
+            y += (from.1 + to.1) * cross;
+        }
+        if area == 0f64 {
+            return None;
+        }
+        let scale = 1f64 / (3f64 * area);
+        Some(origin + Vec2::new((x * scale) as f32, (y * scale) as f32))
+    }
+
+    /// Tuple of vectors to previous and to next vertex for `i`-th vertex;
     /// * `i` - index of vertex. May be negative;
-    pub fn concave(&self, i: isize) -> bool {
-        !self.convex(i)
+    pub fn to_neighbors(&self, i: isize) -> (Vec2, Vec2) {
+        let (prev, that, next) = self.prev_that_next(i);
+        (prev - that, next - that)
     }
 
     /// `sin()` and `cos()` for counter-clockwise angle between vector to next vertex and vector
@@ -71,21 +206,23 @@ impl Outline {
     /// # Arguments
     /// * `i` - index of vertex. May be negative;
     pub fn outer_angle(&self, i: isize) -> f32 {
-        return 2f32 * std::f32::consts::PI - self.inner_angle(i);
+        2f32 * std::f32::consts::PI - self.inner_angle(i)
     }
 }
 
-impl Index<isize> for Outline {
-    type Output = Vec2;
+/// Vertex with index wrapped around number of vertices. Panics on empty outline, so
+/// [`Outline::try_new`] should be used for untrusted vertices.
+impl<P> Index<isize> for Outline<P> {
+    type Output = P;
 
-    fn index(&self, i: isize) -> &Vec2 {
+    fn index(&self, i: isize) -> &P {
         &self.vertices[i.rem_euclid(self.vertices.len() as isize) as usize]
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Outline;
+    use super::{Orientation, Outline};
     use glam::Vec2;
 
     fn default_verts() -> Vec<Vec2> {
@@ -154,6 +291,25 @@ mod tests {
         assert!(outline.concave(1));
     }
 
+    #[test]
+    fn convex_near_collinear() {
+        // Unit in the last place of numbers in range [16, 32).
+        let ulp = 1f32 / (1 << 19) as f32;
+        let a = Vec2::new(0.5f32, 0.5f32);
+        let b = Vec2::new(12f32, 12f32);
+        for &(dy, convex) in &[(ulp, true), (0f32, false), (-ulp, false)] {
+            let c = Vec2::new(24f32, 24f32 + dy);
+            let outline = Outline::new(vec![a, b, c].into_iter());
+            assert_eq!(outline.convex(1), convex);
+            assert_eq!(outline.concave(1), !convex);
+        }
+
+        let degenerate = Outline::new(vec![a, a, b].into_iter());
+        assert!(!degenerate.convex(0));
+        assert!(!degenerate.convex(1));
+        assert!(degenerate.concave(1));
+    }
+
     #[test]
     fn inner_angle() {
         let a = Vec2::new(0f32, 0f32);
@@ -175,4 +331,116 @@ mod tests {
         assert_eq!(outline.outer_angle(0), 7f32 * std::f32::consts::FRAC_PI_4);
         assert_eq!(outline.outer_angle(1), 3f32 * std::f32::consts::FRAC_PI_2);
     }
+
+    fn square() -> Outline {
+        let verts = vec![
+            Vec2::new(0f32, 0f32),
+            Vec2::new(2f32, 0f32),
+            Vec2::new(2f32, 2f32),
+            Vec2::new(0f32, 2f32),
+        ];
+        Outline::new(verts.into_iter())
+    }
+
+    #[test]
+    fn edges() {
+        let outline = square();
+        let edges: Vec<_> = outline.edges().collect();
+        assert_eq!(edges.len(), 4);
+        assert_eq!(edges[0], (outline[0], outline[1]));
+        assert_eq!(edges[3], (outline[3], outline[0]));
+    }
+
+    #[test]
+    fn signed_area() {
+        let outline = square();
+        assert_eq!(outline.signed_area(), 4f32);
+
+        let reversed = Outline::new(outline.vertices().iter().rev().copied());
+        assert_eq!(reversed.signed_area(), -4f32);
+    }
+
+    #[test]
+    fn orientation() {
+        let mut outline = square();
+        assert_eq!(outline.orientation(), Orientation::CounterClockwise);
+        assert!(outline.is_ccw());
+        assert!(!outline.ensure_ccw());
+
+        outline.reverse();
+        assert_eq!(outline.orientation(), Orientation::Clockwise);
+        assert_eq!(outline[0], Vec2::new(0f32, 2f32));
+        assert!(outline.ensure_ccw());
+        assert_eq!(outline, square());
+
+        let line = Outline::new(vec![[0i64, 0], [1, 1], [2, 2]].into_iter());
+        assert_eq!(line.orientation(), Orientation::Degenerate);
+        assert!(!line.is_ccw());
+        assert_eq!(
+            Outline::<Vec2>::new(std::iter::empty()).orientation(),
+            Orientation::Degenerate
+        );
+    }
+
+    #[test]
+    fn centroid() {
+        let offset = Vec2::new(1000f32, -500f32);
+        let outline = Outline::new(square().vertices().iter().map(|&v| v + offset));
+        assert_eq!(outline.centroid(), Some(Vec2::new(1f32, 1f32) + offset));
+
+        // L-shape of three unit squares.
+        let l_shape = Outline::new(
+            vec![
+                (0f32, 0f32),
+                (2f32, 0f32),
+                (2f32, 1f32),
+                (1f32, 1f32),
+                (1f32, 2f32),
+                (0f32, 2f32),
+            ]
+            .into_iter()
+            .map(|(x, y)| Vec2::new(x, y)),
+        );
+        let centroid = l_shape.centroid().unwrap();
+        assert!((centroid - Vec2::new(5f32 / 6f32, 5f32 / 6f32)).length() < 1e-6f32);
+
+        let line = Outline::new(vec![Vec2::zero(), Vec2::one()].into_iter());
+        assert_eq!(line.centroid(), None);
+        assert_eq!(Outline::new(std::iter::empty()).centroid(), None);
+    }
+
+    #[test]
+    fn perimeter() {
+        assert_eq!(square().perimeter(), 8f32);
+    }
+
+    #[test]
+    fn contains() {
+        let outline = square();
+        assert!(outline.contains(Vec2::new(1f32, 1f32)));
+        assert!(outline.contains(Vec2::new(0.1f32, 1.9f32)));
+        assert!(!outline.contains(Vec2::new(3f32, 1f32)));
+        assert!(!outline.contains(Vec2::new(-1f32, 1f32)));
+    }
+
+    #[test]
+    fn generic_points() {
+        let square = square();
+        let precise = square.map(|v| [f64::from(v.x()), f64::from(v.y())]);
+        assert_eq!(precise.doubled_signed_area(), 8f64);
+        assert!(precise.convex(0));
+        assert!(precise.contains([1f64, 1f64]));
+        assert!(!precise.contains([3f64, 1f64]));
+
+        // Area of huge integer square is exact.
+        let size = 1i64 << 40;
+        let integer = Outline::new(vec![[0, 0], [size, 0], [size, size], [0, size]].into_iter());
+        assert_eq!(integer.doubled_signed_area(), 2i128 << 80);
+        assert!(integer.contains([size - 1, 1]));
+        assert!(!integer.contains([size + 1, 1]));
+        assert_eq!(
+            integer.map(|[x, y]| [x as f64, y as f64])[2],
+            [size as f64; 2]
+        );
+    }
 }
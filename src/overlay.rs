@@ -0,0 +1,596 @@
+use crate::geometry::{cross64, orient};
+use crate::outline::Outline;
+use crate::point::{Point, Scalar};
+use crate::polygon::Polygon;
+use crate::sweep::segment_intersections;
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+/// Directed edge of operand with index `.2`. Inner area of operand is at the left side.
+pub(crate) type Segment<P = Vec2> = (P, P, usize);
+
+/// Number of operands supported by overlay.
+pub(crate) const OPERANDS: usize = 2;
+
+/// Point type supported by overlay. Topology is decided by predicates, so only positions of
+/// intersection points are rounded.
+pub(crate) trait OverlayPoint: Point {
+    /// Hashable key of point. Equal points have equal keys.
+    fn key(self) -> (u64, u64);
+
+    /// Intersection of segments `ab` and `cd`, which cross at single inner point of both,
+    /// rounded to representable point.
+    fn intersection(a: Self, b: Self, c: Self, d: Self) -> Self;
+
+    /// Test if middle of segment (`a`, `b`) is inside of `ring`.
+    fn middle_inside(ring: &Outline<Self>, a: Self, b: Self) -> bool;
+}
+
+fn sub(a: Vec2, b: Vec2) -> (f64, f64) {
+    (
+        f64::from(a.x()) - f64::from(b.x()),
+        f64::from(a.y()) - f64::from(b.y()),
+    )
+}
+
+impl OverlayPoint for Vec2 {
+    /// Treats `0.0` and `-0.0` as equal.
+    fn key(self) -> (u64, u64) {
+        (
+            u64::from((self.x() + 0f32).to_bits()),
+            u64::from((self.y() + 0f32).to_bits()),
+        )
+    }
+
+    fn intersection(a: Self, b: Self, c: Self, d: Self) -> Self {
+        let (d3, d4) = (orient(c, d, a), orient(c, d, b));
+        let t = d3 / (d3 - d4);
+        let (dx, dy) = sub(b, a);
+        Vec2::new(
+            (f64::from(a.x()) + t * dx) as f32,
+            (f64::from(a.y()) + t * dy) as f32,
+        )
+    }
+
+    fn middle_inside(ring: &Outline<Self>, a: Self, b: Self) -> bool {
+        ring.contains((a + b) * 0.5f32)
+    }
+}
+
+/// Float arrays compute in `f64` like [`Vec2`], so only intersection points are rounded.
+macro_rules! float_overlay_point {
+    ($($float:ty),*) => {$(
+        impl OverlayPoint for [$float; 2] {
+            /// Treats `0.0` and `-0.0` as equal.
+            fn key(self) -> (u64, u64) {
+                let bits = |value: $float| (f64::from(value) + 0f64).to_bits();
+                (bits(self[0]), bits(self[1]))
+            }
+
+            fn intersection(a: Self, b: Self, c: Self, d: Self) -> Self {
+                let [a, b, c, d] = [a, b, c, d].map(|p| [f64::from(p[0]), f64::from(p[1])]);
+                let (d3, d4) = (cross64(c, d, c, a), cross64(c, d, c, b));
+                let t = d3 / (d3 - d4);
+                let along = |k: usize| <$float>::from_f64(a[k] + t * (b[k] - a[k]));
+                [along(0), along(1)]
+            }
+
+            fn middle_inside(ring: &Outline<Self>, a: Self, b: Self) -> bool {
+                let half = <$float>::from_f64(0.5f64);
+                ring.contains([(a[0] + b[0]) * half, (a[1] + b[1]) * half])
+            }
+        }
+    )*};
+}
+
+float_overlay_point!(f32, f64);
+
+fn cross128(a: [i128; 2], b: [i128; 2]) -> i128 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+/// `a * b / c` rounded half away from zero. Product may exceed `i128`.
+fn mul_div_round(a: i128, b: i128, c: i128) -> i128 {
+    let negative = ((a < 0) != (b < 0)) != (c < 0);
+    let (a, b, c) = (a.unsigned_abs(), b.unsigned_abs(), c.unsigned_abs());
+    // 256-bit product from 64-bit limbs.
+    let (a_hi, a_lo) = (a >> 64, a & u128::from(u64::MAX));
+    let (b_hi, b_lo) = (b >> 64, b & u128::from(u64::MAX));
+    let (low, middle1, middle2, high) = (a_lo * b_lo, a_lo * b_hi, a_hi * b_lo, a_hi * b_hi);
+    let (middle, middle_carry) = middle1.overflowing_add(middle2);
+    let (lo, lo_carry) = low.overflowing_add(middle << 64);
+    let hi = high + (middle >> 64) + (u128::from(middle_carry) << 64) + u128::from(lo_carry);
+    // Long division, remainder stays below `c`.
+    let (mut quotient, mut remainder) = (0u128, 0u128);
+    for bit in (0..256).rev() {
+        let next = if bit >= 128 {
+            (hi >> (bit - 128)) & 1
+        } else {
+            (lo >> bit) & 1
+        };
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | next;
+        quotient <<= 1;
+        if carry != 0 || remainder >= c {
+            remainder = remainder.wrapping_sub(c);
+            quotient |= 1;
+        }
+    }
+    if remainder >= c - remainder {
+        quotient += 1;
+    }
+    let quotient = quotient as i128;
+    if negative {
+        -quotient
+    } else {
+        quotient
+    }
+}
+
+/// Integer points compute in `i128`, every decision is exact while coordinates don't exceed
+/// `2^53` by magnitude, so they are exact in `f64` of intersection sweep. Intersection points
+/// are rounded to the nearest integer point.
+macro_rules! int_overlay_point {
+    ($($int:ty),*) => {$(
+        impl OverlayPoint for [$int; 2] {
+            fn key(self) -> (u64, u64) {
+                (self[0] as i64 as u64, self[1] as i64 as u64)
+            }
+
+            fn intersection(a: Self, b: Self, c: Self, d: Self) -> Self {
+                let [a, b, c, d] = [a, b, c, d].map(|p| [i128::from(p[0]), i128::from(p[1])]);
+                let diff = |p: [i128; 2], q: [i128; 2]| [p[0] - q[0], p[1] - q[1]];
+                let d3 = cross128(diff(d, c), diff(a, c));
+                let d4 = cross128(diff(d, c), diff(b, c));
+                let dir = diff(b, a);
+                let along = |k: usize| a[k] + mul_div_round(dir[k], d3, d3 - d4);
+                [along(0) as $int, along(1) as $int]
+            }
+
+            fn middle_inside(ring: &Outline<Self>, a: Self, b: Self) -> bool {
+                let middle = [
+                    i128::from(a[0]) + i128::from(b[0]),
+                    i128::from(a[1]) + i128::from(b[1]),
+                ];
+                let mut inside = false;
+                for (from, to) in ring.edges() {
+                    let [from, to] = [from, to].map(|p| [i128::from(p[0]), i128::from(p[1])]);
+                    let upward = 2 * to[1] > middle[1];
+                    if (2 * from[1] > middle[1]) != upward {
+                        let edge = [to[0] - from[0], to[1] - from[1]];
+                        let to_middle = [middle[0] - 2 * from[0], middle[1] - 2 * from[1]];
+                        let side = cross128(edge, to_middle);
+                        if side != 0 && (side > 0) == upward {
+                            inside = !inside;
+                        }
+                    }
+                }
+                inside
+            }
+        }
+    )*};
+}
+
+int_overlay_point!(i32, i64);
+
+/// Edges of all rings of `polygon` marked with `owner`.
+pub(crate) fn segments<P: OverlayPoint>(
+    polygon: &Polygon<P>,
+    owner: usize,
+) -> impl Iterator<Item = Segment<P>> + '_ {
+    polygon
+        .rings()
+        .flat_map(move |ring| ring.edges().map(move |(from, to)| (from, to, owner)))
+}
+
+/// Builds polygons from boundary of region selected by `inside` predicate. Predicate receives
+/// winding numbers of point for every operand.
+pub(crate) fn overlay<P: OverlayPoint>(
+    segments: &[Segment<P>],
+    inside: impl Fn([i32; OPERANDS]) -> bool,
+) -> Vec<Polygon<P>> {
+    let edges = merge_coincident(&split_segments(segments));
+    let mut directed = Vec::new();
+    for (edge, right) in edges.iter().zip(windings_right(&edges)) {
+        let mut left = right;
+        for (w, delta) in left.iter_mut().zip(edge.delta.iter()) {
+            *w += delta;
+        }
+        match (inside(left), inside(right)) {
+            (true, false) => directed.push((edge.from, edge.to)),
+            (false, true) => directed.push((edge.to, edge.from)),
+            _ => {}
+        }
+    }
+    group_rings(assemble_rings(&directed))
+}
+
+/// Unique undirected edge. `delta` is difference of winding numbers at the left and at the
+/// right side of edge for every operand.
+struct Edge<P> {
+    from: P,
+    to: P,
+    delta: [i32; OPERANDS],
+}
+
+fn min_max<S: Scalar>(a: S, b: S) -> (S, S) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Test if `p` lies inside of bounding box of segment `ab` and doesn't coincide with its ends.
+fn strictly_within<P: Point>(a: P, b: P, p: P) -> bool {
+    let ((min_x, max_x), (min_y, max_y)) = (min_max(a.x(), b.x()), min_max(a.y(), b.y()));
+    p != a && p != b && p.x() >= min_x && p.x() <= max_x && p.y() >= min_y && p.y() <= max_y
+}
+
+/// Lexicographic order of points.
+fn cmp_xy<P: Point>(p: &P, q: &P) -> Ordering {
+    p.x()
+        .partial_cmp(&q.x())
+        .unwrap_or(Ordering::Equal)
+        .then(p.y().partial_cmp(&q.y()).unwrap_or(Ordering::Equal))
+}
+
+/// Test if collinear points `a` and `b` lie in the same direction from `v`.
+fn same_direction<P: Point>(v: P, a: P, b: P) -> bool {
+    let cmp = |s: P::Scalar, t: P::Scalar| s.partial_cmp(&t).unwrap_or(Ordering::Equal);
+    a != v && cmp(a.x(), v.x()) == cmp(b.x(), v.x()) && cmp(a.y(), v.y()) == cmp(b.y(), v.y())
+}
+
+/// Splits segments at all their intersections, so they have common points only at ends.
+/// Rounded crossing points move pieces of segments, which may cross other segments then, so
+/// splitting is repeated until no segment is cut.
+pub(crate) fn split_segments<P: OverlayPoint>(segments: &[Segment<P>]) -> Vec<Segment<P>> {
+    let mut segments: Vec<Segment<P>> = segments.iter().copied().filter(|s| s.0 != s.1).collect();
+    loop {
+        let split = split_once(&segments);
+        if split.len() == segments.len() {
+            return split;
+        }
+        segments = split;
+    }
+}
+
+/// Cuts segments at their intersections found by sweep.
+fn split_once<P: OverlayPoint>(segments: &[Segment<P>]) -> Vec<Segment<P>> {
+    let ends: Vec<(P, P)> = segments.iter().map(|&(a, b, _)| (a, b)).collect();
+    let mut cuts: Vec<Vec<P>> = vec![Vec::new(); segments.len()];
+    for (i, j) in segment_intersections(&ends) {
+        let ((a, b), (c, d)) = (ends[i], ends[j]);
+        let (d1, d2) = (P::orient(a, b, c), P::orient(a, b, d));
+        let (d3, d4) = (P::orient(c, d, a), P::orient(c, d, b));
+        let opposite = |x: Ordering, y: Ordering| x != Ordering::Equal && x == y.reverse();
+        if opposite(d1, d2) && opposite(d3, d4) {
+            let p = P::intersection(a, b, c, d);
+            cuts[i].push(p);
+            cuts[j].push(p);
+            continue;
+        }
+        if d1 == Ordering::Equal && strictly_within(a, b, c) {
+            cuts[i].push(c);
+        }
+        if d2 == Ordering::Equal && strictly_within(a, b, d) {
+            cuts[i].push(d);
+        }
+        if d3 == Ordering::Equal && strictly_within(c, d, a) {
+            cuts[j].push(a);
+        }
+        if d4 == Ordering::Equal && strictly_within(c, d, b) {
+            cuts[j].push(b);
+        }
+    }
+
+    let mut result = Vec::with_capacity(segments.len());
+    for (&(a, b, owner), mut points) in segments.iter().zip(cuts) {
+        // Points on segment follow in lexicographic order from its smaller end.
+        if cmp_xy(&a, &b) == Ordering::Less {
+            points.sort_by(cmp_xy);
+        } else {
+            points.sort_by(|p, q| cmp_xy(q, p));
+        }
+        let mut from = a;
+        for p in points.into_iter().chain(std::iter::once(b)) {
+            if p != from {
+                result.push((from, p, owner));
+                from = p;
+            }
+        }
+    }
+    result
+}
+
+/// Merges coincident segments into unique edges, accumulating winding deltas.
+fn merge_coincident<P: OverlayPoint>(segments: &[Segment<P>]) -> Vec<Edge<P>> {
+    let mut index = HashMap::with_capacity(segments.len());
+    let mut edges: Vec<Edge<P>> = Vec::with_capacity(segments.len());
+    for &(a, b, owner) in segments {
+        let (ka, kb) = (a.key(), b.key());
+        let (from, to, sign) = if ka < kb { (a, b, 1) } else { (b, a, -1) };
+        let k = *index.entry((from.key(), to.key())).or_insert_with(|| {
+            edges.push(Edge {
+                from,
+                to,
+                delta: [0; OPERANDS],
+            });
+            edges.len() - 1
+        });
+        edges[k].delta[owner] += sign;
+    }
+    edges.retain(|edge| edge.delta.iter().any(|&d| d != 0));
+    edges
+}
+
+/// Edge crossing sweep line from `left` to lexicographically greater `right` end.
+#[derive(Debug, Clone, Copy)]
+struct Spanning<P> {
+    left: P,
+    right: P,
+    edge: usize,
+}
+
+impl<P: Point> Spanning<P> {
+    fn new(edge: &Edge<P>, index: usize) -> Self {
+        let (left, right) = if cmp_xy(&edge.from, &edge.to) == Ordering::Less {
+            (edge.from, edge.to)
+        } else {
+            (edge.to, edge.from)
+        };
+        Spanning {
+            left,
+            right,
+            edge: index,
+        }
+    }
+}
+
+impl<P: Point> PartialEq for Spanning<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Point> Eq for Spanning<P> {}
+
+impl<P: Point> PartialOrd for Spanning<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Point> Ord for Spanning<P> {
+    /// Bottom to top order of edges, which both cross sweep line and have common points only
+    /// at ends.
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.edge == other.edge {
+            return Ordering::Equal;
+        }
+        if cmp_xy(&self.left, &other.left) == Ordering::Greater {
+            return other.cmp(self).reverse();
+        }
+        let (a, b) = (self.left, self.right);
+        let side = match P::orient(a, b, other.left) {
+            Ordering::Equal => P::orient(a, b, other.right),
+            side => side,
+        };
+        // `other` at left side of `self` is above it.
+        side.reverse().then(self.edge.cmp(&other.edge))
+    }
+}
+
+/// Winding numbers of operands at the right side of every edge. Edges **MUST** have common
+/// points only at ends. Sweep line moves in lexicographic order of points, so the right side of
+/// edge directed to its greater end is below it. Winding below edge is the winding above the
+/// previous edge on sweep line.
+fn windings_right<P: OverlayPoint>(edges: &[Edge<P>]) -> Vec<[i32; OPERANDS]> {
+    let spanning: Vec<Spanning<P>> = edges
+        .iter()
+        .enumerate()
+        .map(|(k, edge)| Spanning::new(edge, k))
+        .collect();
+    // Edges end before others start at the same point, starting edges go from bottom to top.
+    let mut events: Vec<(bool, Spanning<P>)> = spanning
+        .iter()
+        .flat_map(|&s| vec![(false, s), (true, s)])
+        .collect();
+    let point = |&(start, s): &(bool, Spanning<P>)| if start { s.left } else { s.right };
+    events.sort_by(|x, y| {
+        cmp_xy(&point(x), &point(y))
+            .then(x.0.cmp(&y.0))
+            .then_with(|| x.1.cmp(&y.1))
+    });
+
+    let mut status = BTreeSet::new();
+    let mut above = vec![[0; OPERANDS]; edges.len()];
+    let mut right = vec![[0; OPERANDS]; edges.len()];
+    for (start, s) in events {
+        if !start {
+            status.remove(&s);
+            continue;
+        }
+        let below = status
+            .range(..s)
+            .next_back()
+            .map_or([0; OPERANDS], |previous: &Spanning<P>| above[previous.edge]);
+        let edge = &edges[s.edge];
+        let forward = edge.from == s.left;
+        let mut up = below;
+        for (w, delta) in up.iter_mut().zip(edge.delta.iter()) {
+            *w += if forward { *delta } else { -delta };
+        }
+        above[s.edge] = up;
+        right[s.edge] = if forward { below } else { up };
+        status.insert(s);
+    }
+    right
+}
+
+/// Position of direction from `v` to `a` in clockwise order starting after direction from `v`
+/// to `r`: less than half turn, exactly half turn, more than half turn or full turn.
+fn clockwise_half<P: Point>(v: P, r: P, a: P) -> u8 {
+    match P::orient(v, r, a) {
+        Ordering::Less => 0,
+        Ordering::Greater => 2,
+        Ordering::Equal if same_direction(v, r, a) => 3,
+        Ordering::Equal => 1,
+    }
+}
+
+/// Compares directions from `v` to `a` and to `b` by clockwise angle from direction to `r`.
+fn cmp_clockwise<P: Point>(v: P, r: P, a: P, b: P) -> Ordering {
+    let half = clockwise_half(v, r, a);
+    half.cmp(&clockwise_half(v, r, b)).then_with(|| {
+        if half == 0 || half == 2 {
+            P::orient(v, b, a)
+        } else {
+            Ordering::Equal
+        }
+    })
+}
+
+/// Links directed edges into closed rings. At vertex with several outgoing edges the one
+/// closest clockwise to reversed incoming edge is taken, so every ring bounds single face.
+pub(crate) fn assemble_rings<P: OverlayPoint>(edges: &[(P, P)]) -> Vec<Outline<P>> {
+    let mut outgoing: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    for (i, &(from, _)) in edges.iter().enumerate() {
+        outgoing.entry(from.key()).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (from, to) = edges[current];
+            ring.push(from);
+            let next = outgoing[&to.key()]
+                .iter()
+                .copied()
+                .filter(|&e| !used[e] || e == start)
+                .min_by(|&x, &y| cmp_clockwise(to, from, edges[x].1, edges[y].1));
+            match next {
+                Some(next) if next != start => current = next,
+                _ => break,
+            }
+        }
+        let ring = remove_collinear(ring);
+        if ring.len() >= 3 {
+            rings.push(Outline::new(ring.into_iter()));
+        }
+    }
+    rings
+}
+
+/// Removes vertices, which lie on straight line between their neighbors.
+fn remove_collinear<P: Point>(mut ring: Vec<P>) -> Vec<P> {
+    let mut changed = true;
+    while changed && ring.len() >= 3 {
+        changed = false;
+        let len = ring.len();
+        let mut kept = Vec::with_capacity(len);
+        for i in 0..len {
+            let prev = kept.last().copied().unwrap_or(ring[(i + len - 1) % len]);
+            let (that, next) = (ring[i], ring[(i + 1) % len]);
+            let spike = next != that && same_direction(that, prev, next);
+            if P::orient(prev, that, next) == Ordering::Equal && !spike {
+                changed = true;
+            } else {
+                kept.push(that);
+            }
+        }
+        ring = kept;
+    }
+    ring
+}
+
+/// Groups counter-clockwise rings with clockwise holes inside them.
+pub(crate) fn group_rings<P: OverlayPoint>(rings: Vec<Outline<P>>) -> Vec<Polygon<P>> {
+    let zero = P::Area::zero();
+    let (mut outers, holes): (Vec<Outline<P>>, Vec<Outline<P>>) = rings
+        .into_iter()
+        .filter(|ring| ring.doubled_signed_area() != zero)
+        .partition(|ring| ring.doubled_signed_area() > zero);
+    outers.sort_by(|a, b| {
+        a.doubled_signed_area()
+            .partial_cmp(&b.doubled_signed_area())
+            .unwrap_or(Ordering::Equal)
+    });
+    let mut grouped: Vec<Vec<Outline<P>>> = vec![Vec::new(); outers.len()];
+    for hole in holes {
+        // Middle of the longest edge can't touch outer ring.
+        let length = |(a, b): &(P, P)| {
+            let dx = b.x().to_f64() - a.x().to_f64();
+            let dy = b.y().to_f64() - a.y().to_f64();
+            dx * dx + dy * dy
+        };
+        let (a, b) = hole
+            .edges()
+            .max_by(|x, y| length(x).partial_cmp(&length(y)).unwrap_or(Ordering::Equal))
+            .unwrap();
+        if let Some(i) = outers
+            .iter()
+            .position(|outer| P::middle_inside(outer, a, b))
+        {
+            grouped[i].push(hole);
+        }
+    }
+    outers
+        .into_iter()
+        .zip(grouped)
+        .map(|(outer, holes)| {
+            Polygon::new(outer, holes).expect("rings are grouped by sign of area")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_rings, mul_div_round};
+    use crate::outline::Outline;
+    use glam::Vec2;
+
+    fn ring(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn every_ring_is_grouped() {
+        let square =
+            |x: f32, size: f32| ring(&[(x, x), (x + size, x), (x + size, x + size), (x, x + size)]);
+        let reversed = |outline: Outline| Outline::new(outline.vertices().iter().rev().copied());
+        let rings = vec![
+            reversed(square(1f32, 4f32)),
+            square(2f32, 2f32),
+            square(0f32, 6f32),
+            // Zero area ring bounds nothing.
+            ring(&[(0f32, 0f32), (1f32, 1f32), (2f32, 2f32)]),
+        ];
+        let polygons = group_rings(rings);
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].area(), 4f32);
+        assert!(polygons[0].holes().is_empty());
+        assert_eq!(polygons[1].holes().len(), 1);
+        assert_eq!(polygons[1].area(), 20f32);
+    }
+
+    #[test]
+    fn wide_mul_div() {
+        assert_eq!(mul_div_round(7, 3, 2), 11);
+        assert_eq!(mul_div_round(-7, 3, 2), -11);
+        assert_eq!(mul_div_round(7, -3, -4), 5);
+        let big = 1i128 << 120;
+        assert_eq!(mul_div_round(big, big, big), big);
+        assert_eq!(mul_div_round(big + 1, 1 << 100, 1 << 101), (big >> 1) + 1);
+        assert_eq!(mul_div_round(-(big - 1), 3 << 100, big), -(3 << 100));
+        assert_eq!(mul_div_round(big - 1, big - 1, big), big - 2);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::geometry::{cross64, orient};
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+/// Number type of point coordinates
+pub trait Scalar:
+    Copy + PartialOrd + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// Additive identity.
+    fn zero() -> Self;
+
+    /// Nearest `f64` value.
+    fn to_f64(self) -> f64;
+
+    /// Nearest value to `value`. Integers saturate at bounds of type.
+    fn from_f64(value: f64) -> Self;
+}
+
+/// Point on plane, which can be vertex of [`Outline`](crate::Outline)
+///
+/// Algorithms, which decide only by exact predicates, work with every point type: validation,
+/// location, convex hull, simplification, outline triangulation, monotone and convex
+/// decomposition and boolean operations. Algorithms, which construct new positions or measure in
+/// `f32` (triangulation of polygon with holes, concave hulls, offset, skeletons, medial axis,
+/// Delaunay mesh, distances, calipers, oriented rectangles and circles, repair and metrics of
+/// [`Outline`](crate::Outline)), are implemented for [`Vec2`] only. Other points can be converted
+/// with [`Outline::map`](crate::Outline::map) or [`fixed`](crate::fixed) conversions.
+pub trait Point: Copy + PartialEq + Debug {
+    /// Type of coordinates.
+    type Scalar: Scalar;
+    /// Type of cross products and areas. Wider than `Scalar` for integer points, so products
+    /// don't overflow.
+    type Area: Scalar;
+
+    /// Creates point from coordinates.
+    fn from_xy(x: Self::Scalar, y: Self::Scalar) -> Self;
+
+    /// First coordinate.
+    fn x(self) -> Self::Scalar;
+
+    /// Second coordinate.
+    fn y(self) -> Self::Scalar;
+
+    /// Cross product of radius vectors of points.
+    fn cross(self, other: Self) -> Self::Area;
+
+    /// Exact orientation of triangle (`a`, `b`, `c`): [`Ordering::Greater`] if `c` lies at
+    /// left side of directed line from `a` to `b`, [`Ordering::Less`] if at right side and
+    /// [`Ordering::Equal`] if points are collinear.
+    fn orient(a: Self, b: Self, c: Self) -> Ordering;
+}
+
+fn sign(value: f64) -> Ordering {
+    value.partial_cmp(&0f64).unwrap_or(Ordering::Equal)
+}
+
+impl Point for Vec2 {
+    type Scalar = f32;
+    type Area = f32;
+
+    fn from_xy(x: f32, y: f32) -> Self {
+        Vec2::new(x, y)
+    }
+
+    fn x(self) -> f32 {
+        Vec2::x(self)
+    }
+
+    fn y(self) -> f32 {
+        Vec2::y(self)
+    }
+
+    fn cross(self, other: Self) -> f32 {
+        self.perp_dot(other)
+    }
+
+    fn orient(a: Self, b: Self, c: Self) -> Ordering {
+        sign(orient(a, b, c))
+    }
+}
+
+macro_rules! float_scalar {
+    ($($float:ty),*) => {$(
+        impl Scalar for $float {
+            fn zero() -> Self {
+                0 as $float
+            }
+
+            fn to_f64(self) -> f64 {
+                f64::from(self)
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $float
+            }
+        }
+
+        impl Point for [$float; 2] {
+            type Scalar = $float;
+            type Area = $float;
+
+            fn from_xy(x: $float, y: $float) -> Self {
+                [x, y]
+            }
+
+            fn x(self) -> $float {
+                self[0]
+            }
+
+            fn y(self) -> $float {
+                self[1]
+            }
+
+            fn cross(self, other: Self) -> $float {
+                self[0] * other[1] - self[1] * other[0]
+            }
+
+            fn orient(a: Self, b: Self, c: Self) -> Ordering {
+                let [a, b, c] = [a, b, c].map(|p| [f64::from(p[0]), f64::from(p[1])]);
+                sign(cross64(a, b, a, c))
+            }
+        }
+    )*};
+}
+
+float_scalar!(f32, f64);
+
+macro_rules! int_scalar {
+    ($($int:ty),*) => {$(
+        impl Scalar for $int {
+            fn zero() -> Self {
+                0
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value.round() as $int
+            }
+        }
+    )*};
+}
+
+int_scalar!(i32, i64, i128);
+
+/// Largest magnitude of integer coordinates, for which boolean operations are exact.
+pub(crate) const EXACT_INTEGER: i64 = 1 << 53;
+
+/// Integer points use `i128` arithmetic, so products and orientations don't overflow. Boolean
+/// operations are exact while coordinates don't exceed `2^53` by magnitude.
+macro_rules! int_point {
+    ($($int:ty),*) => {$(
+        impl Point for [$int; 2] {
+            type Scalar = $int;
+            type Area = i128;
+
+            fn from_xy(x: $int, y: $int) -> Self {
+                [x, y]
+            }
+
+            fn x(self) -> $int {
+                self[0]
+            }
+
+            fn y(self) -> $int {
+                self[1]
+            }
+
+            fn cross(self, other: Self) -> i128 {
+                i128::from(self[0]) * i128::from(other[1])
+                    - i128::from(self[1]) * i128::from(other[0])
+            }
+
+            fn orient(a: Self, b: Self, c: Self) -> Ordering {
+                let d = |p: Self, q: Self, k: usize| i128::from(p[k]) - i128::from(q[k]);
+                (d(b, a, 0) * d(c, a, 1) - d(b, a, 1) * d(c, a, 0)).cmp(&0)
+            }
+        }
+    )*};
+}
+
+int_point!(i32, i64);
+
+#[cfg(test)]
+mod tests {
+    use super::Point;
+    use glam::Vec2;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn orientation_agrees() {
+        let (a, b) = ((0, 0), (4, 2));
+        for &(c, expected) in &[
+            ((1, 1), Ordering::Greater),
+            ((2, 1), Ordering::Equal),
+            ((3, 1), Ordering::Less),
+        ] {
+            let v = |p: (i32, i32)| Vec2::new(p.0 as f32, p.1 as f32);
+            let d = |p: (i32, i32)| [f64::from(p.0), f64::from(p.1)];
+            let i = |p: (i32, i32)| [i64::from(p.0), i64::from(p.1)];
+            assert_eq!(Point::orient(v(a), v(b), v(c)), expected);
+            assert_eq!(Point::orient(d(a), d(b), d(c)), expected);
+            assert_eq!(Point::orient([a.0, a.1], [b.0, b.1], [c.0, c.1]), expected);
+            assert_eq!(Point::orient(i(a), i(b), i(c)), expected);
+        }
+    }
+
+    #[test]
+    fn f64_precision() {
+        // Offset is lost in `f32`, but kept in `f64`.
+        let (a, b) = ([1e8f64, 0f64], [1e8f64 + 2f64, 2f64]);
+        let c = [1e8f64 + 1f64, 1f64 + 1e-9f64];
+        assert_eq!(Point::orient(a, b, c), Ordering::Greater);
+    }
+
+    #[test]
+    fn large_integers() {
+        let big = 1i64 << 60;
+        let (a, b, c) = ([-big, -big], [big, big], [big - 1, big]);
+        assert_eq!(Point::orient(a, b, c), Ordering::Greater);
+        assert_eq!([big, 0].cross([0, big]), 1i128 << 120);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::outline::Outline;
+use crate::point::{Point, Scalar};
+use glam::Vec2;
+use std::error::Error;
+use std::fmt;
+
+/// Region bounded by outer outline with optional holes in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon<P = Vec2> {
+    outline: Outline<P>,
+    holes: Vec<Outline<P>>,
+}
+
+/// Reason of polygon construction failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolygonError {
+    /// Outer outline is not counter-clockwise (or has zero area).
+    OutlineNotCounterClockwise,
+    /// Hole with specified index is not clockwise (or has zero area).
+    HoleNotClockwise(usize),
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolygonError::OutlineNotCounterClockwise => {
+                write!(f, "outer outline must be counter-clockwise")
+            }
+            PolygonError::HoleNotClockwise(i) => write!(f, "hole {} must be clockwise", i),
+        }
+    }
+}
+
+impl Error for PolygonError {}
+
+impl<P: Point> Polygon<P> {
+    /// Creates new polygon.
+    /// # Arguments
+    /// * `outline` - outer boundary. **MUST** be counter-clockwise;
+    /// * `holes` - boundaries of holes. Each **MUST** be clockwise, so inner area of polygon
+    ///   is at left side of every edge;
+    pub fn new(outline: Outline<P>, holes: Vec<Outline<P>>) -> Result<Self, PolygonError> {
+        let zero = P::Area::zero();
+        if outline.doubled_signed_area() <= zero {
+            return Err(PolygonError::OutlineNotCounterClockwise);
+        }
+        if let Some(i) = holes
+            .iter()
+            .position(|hole| hole.doubled_signed_area() >= zero)
+        {
+            return Err(PolygonError::HoleNotClockwise(i));
+        }
+        Ok(Polygon { outline, holes })
+    }
+
+    /// Creates new polygon without holes.
+    /// # Arguments
+    /// * `outline` - outer boundary. **MUST** be counter-clockwise;
+    pub fn without_holes(outline: Outline<P>) -> Result<Self, PolygonError> {
+        Self::new(outline, Vec::new())
+    }
+
+    /// Outer boundary.
+    pub fn outline(&self) -> &Outline<P> {
+        &self.outline
+    }
+
+    /// Boundaries of holes.
+    pub fn holes(&self) -> &[Outline<P>] {
+        &self.holes
+    }
+
+    /// Iterator over all rings: outer outline first, then holes.
+    pub fn rings(&self) -> impl Iterator<Item = &Outline<P>> {
+        std::iter::once(&self.outline).chain(self.holes.iter())
+    }
+
+    /// Iterator over vertices of all rings, in order of [`Polygon::rings`].
+    pub fn vertices(&self) -> impl Iterator<Item = P> + '_ {
+        self.rings()
+            .flat_map(|ring| ring.vertices().iter().copied())
+    }
+
+    /// Splits polygon into outer outline and holes.
+    pub fn into_parts(self) -> (Outline<P>, Vec<Outline<P>>) {
+        (self.outline, self.holes)
+    }
+
+    /// Polygon with every vertex converted by `f`. Conversion **MUST** keep orientation of
+    /// rings.
+    pub fn map<Q: Point>(&self, mut f: impl FnMut(P) -> Q) -> Polygon<Q> {
+        Polygon {
+            outline: self.outline.map(&mut f),
+            holes: self.holes.iter().map(|hole| hole.map(&mut f)).collect(),
+        }
+    }
+
+    /// Test if `point` is inside outer outline and outside of every hole.
+    /// Result for points exactly on boundary is unspecified, see [`Polygon::locate`].
+    pub fn contains(&self, point: P) -> bool {
+        self.outline.contains(point) && !self.holes.iter().any(|hole| hole.contains(point))
+    }
+}
+
+impl Polygon {
+    /// Area of polygon without area of holes.
+    pub fn area(&self) -> f32 {
+        self.rings().map(Outline::signed_area).sum()
+    }
+
+    /// Total length of all rings.
+    pub fn perimeter(&self) -> f32 {
+        self.rings().map(Outline::perimeter).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Polygon, PolygonError};
+    use crate::outline::Outline;
+    use glam::Vec2;
+
+    fn rect(min: Vec2, max: Vec2) -> Outline {
+        let verts = vec![
+            min,
+            Vec2::new(max.x(), min.y()),
+            max,
+            Vec2::new(min.x(), max.y()),
+        ];
+        Outline::new(verts.into_iter())
+    }
+
+    fn hole(min: Vec2, max: Vec2) -> Outline {
+        let outline = rect(min, max);
+        Outline::new(outline.vertices().iter().rev().copied())
+    }
+
+    fn with_hole() -> Polygon {
+        let outline = rect(Vec2::new(0f32, 0f32), Vec2::new(4f32, 4f32));
+        let holes = vec![hole(Vec2::new(1f32, 1f32), Vec2::new(2f32, 2f32))];
+        Polygon::new(outline, holes).unwrap()
+    }
+
+    #[test]
+    fn orientation_checks() {
+        let outer = rect(Vec2::new(0f32, 0f32), Vec2::new(4f32, 4f32));
+        let inner = rect(Vec2::new(1f32, 1f32), Vec2::new(2f32, 2f32));
+        let err = Polygon::new(outer.clone(), vec![inner]).unwrap_err();
+        assert_eq!(err, PolygonError::HoleNotClockwise(0));
+
+        let reversed = hole(Vec2::new(0f32, 0f32), Vec2::new(4f32, 4f32));
+        let err = Polygon::without_holes(reversed).unwrap_err();
+        assert_eq!(err, PolygonError::OutlineNotCounterClockwise);
+
+        assert!(Polygon::without_holes(outer).is_ok());
+    }
+
+    #[test]
+    fn rings() {
+        let polygon = with_hole();
+        assert_eq!(polygon.rings().count(), 2);
+        assert_eq!(polygon.rings().next(), Some(polygon.outline()));
+        assert_eq!(polygon.rings().nth(1), Some(&polygon.holes()[0]));
+    }
+
+    #[test]
+    fn area_and_perimeter() {
+        let polygon = with_hole();
+        assert_eq!(polygon.area(), 15f32);
+        assert_eq!(polygon.perimeter(), 20f32);
+    }
+
+    #[test]
+    fn contains() {
+        let polygon = with_hole();
+        assert!(polygon.contains(Vec2::new(3f32, 3f32)));
+        assert!(!polygon.contains(Vec2::new(1.5f32, 1.5f32)));
+        assert!(!polygon.contains(Vec2::new(5f32, 1.5f32)));
+    }
+}
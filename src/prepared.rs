@@ -0,0 +1,388 @@
+//! Outline with bounding volume hierarchy of edges for fast repeated queries.
+//!
+//! Hierarchy is built once in `O(n log n)` time. Queries visit only nodes, which bounding
+//! boxes may affect result, so they take `O(log n)` time for typical outlines.
+use crate::geometry::closest_on_segment;
+use crate::location::{near, winding_delta, FillRule, Location};
+use crate::outline::Outline;
+use crate::point::{Point, Scalar};
+use crate::validation::intersection;
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// Maximum number of edges in leaf node.
+const LEAF_SIZE: usize = 4;
+
+type Xy = [f64; 2];
+
+fn xy<P: Point>(p: P) -> Xy {
+    [p.x().to_f64(), p.y().to_f64()]
+}
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min: Xy,
+    max: Xy,
+}
+
+impl Bounds {
+    fn new(a: Xy, b: Xy) -> Self {
+        Bounds {
+            min: [a[0].min(b[0]), a[1].min(b[1])],
+            max: [a[0].max(b[0]), a[1].max(b[1])],
+        }
+    }
+
+    fn union(self, other: Bounds) -> Self {
+        Bounds {
+            min: [self.min[0].min(other.min[0]), self.min[1].min(other.min[1])],
+            max: [self.max[0].max(other.max[0]), self.max[1].max(other.max[1])],
+        }
+    }
+
+    fn overlaps(&self, other: &Bounds) -> bool {
+        (0..2).all(|k| self.min[k] <= other.max[k] && other.min[k] <= self.max[k])
+    }
+
+    /// Squared distance from `p` to the nearest point of box.
+    fn distance_squared(&self, p: Xy) -> f64 {
+        let d = |k: usize| (self.min[k] - p[k]).max(p[k] - self.max[k]).max(0f64);
+        d(0) * d(0) + d(1) * d(1)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    /// Box of edges with indices in specified range of `PreparedOutline::edges`.
+    Leaf(Bounds, Range<usize>),
+    /// Box of two child nodes.
+    Branch(Bounds, usize, usize),
+}
+
+impl Node {
+    fn bounds(&self) -> &Bounds {
+        match self {
+            Node::Leaf(bounds, _) | Node::Branch(bounds, ..) => bounds,
+        }
+    }
+}
+
+/// Builds node for `edges`, which start at `offset` in the whole list, and its children.
+fn build(nodes: &mut Vec<Node>, boxes: &[Bounds], edges: &mut [usize], offset: usize) -> usize {
+    let bounds = edges
+        .iter()
+        .map(|&e| boxes[e])
+        .fold(boxes[edges[0]], Bounds::union);
+    let index = nodes.len();
+    if edges.len() <= LEAF_SIZE {
+        nodes.push(Node::Leaf(bounds, offset..offset + edges.len()));
+        return index;
+    }
+    // Placeholder is replaced after children are built.
+    nodes.push(Node::Leaf(bounds, 0..0));
+    let k = usize::from(bounds.max[1] - bounds.min[1] > bounds.max[0] - bounds.min[0]);
+    let center = |e: usize| boxes[e].min[k] + boxes[e].max[k];
+    let middle = edges.len() / 2;
+    edges.select_nth_unstable_by(middle, |&a, &b| {
+        center(a).partial_cmp(&center(b)).unwrap_or(Ordering::Equal)
+    });
+    let (low, high) = edges.split_at_mut(middle);
+    let left = build(nodes, boxes, low, offset);
+    let right = build(nodes, boxes, high, offset + middle);
+    nodes[index] = Node::Branch(bounds, left, right);
+    index
+}
+
+/// Outline prepared for many location, nearest edge and segment queries. Results are the same
+/// as of linear queries of [`Outline`]. Results for outline with non-finite vertices are
+/// unspecified.
+#[derive(Debug, Clone)]
+pub struct PreparedOutline<P = Vec2> {
+    outline: Outline<P>,
+    /// Indices of edges ordered so, that every leaf covers continuous range.
+    edges: Vec<usize>,
+    /// Hierarchy with root at index zero. Empty for empty outline.
+    nodes: Vec<Node>,
+}
+
+impl<P: Point> PreparedOutline<P> {
+    /// Builds hierarchy of `outline` edges.
+    pub fn new(outline: Outline<P>) -> Self {
+        let boxes: Vec<Bounds> = outline
+            .edges()
+            .map(|(a, b)| Bounds::new(xy(a), xy(b)))
+            .collect();
+        let mut edges: Vec<usize> = (0..boxes.len()).collect();
+        let mut nodes = Vec::with_capacity(2 * boxes.len() / LEAF_SIZE + 1);
+        if !edges.is_empty() {
+            build(&mut nodes, &boxes, &mut edges, 0);
+        }
+        PreparedOutline {
+            outline,
+            edges,
+            nodes,
+        }
+    }
+
+    /// Prepared outline.
+    pub fn outline(&self) -> &Outline<P> {
+        &self.outline
+    }
+
+    /// Returns prepared outline dropping hierarchy.
+    pub fn into_outline(self) -> Outline<P> {
+        self.outline
+    }
+
+    fn edge(&self, i: usize) -> (P, P) {
+        self.outline.edge(i as isize)
+    }
+
+    /// Calls `edge` for edges in every leaf, which box passes `enter`, until `edge` returns
+    /// `true`.
+    fn visit(&self, enter: impl Fn(&Bounds) -> bool, mut edge: impl FnMut(usize) -> bool) {
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if !enter(node.bounds()) {
+                continue;
+            }
+            match node {
+                Node::Leaf(_, range) => {
+                    if self.edges[range.clone()].iter().any(|&e| edge(e)) {
+                        return;
+                    }
+                }
+                Node::Branch(_, left, right) => {
+                    stack.push(*right);
+                    stack.push(*left);
+                }
+            }
+        }
+    }
+
+    /// Number of times outline winds counter-clockwise around `point`.
+    /// See [`Outline::winding_number`].
+    pub fn winding_number(&self, point: P) -> i32 {
+        let p = xy(point);
+        let mut winding = 0;
+        // Only edges crossing horizontal ray to the right of point change winding number.
+        let enter = |b: &Bounds| b.min[1] <= p[1] && p[1] <= b.max[1] && p[0] <= b.max[0];
+        self.visit(enter, |e| {
+            let (a, b) = self.edge(e);
+            winding += winding_delta(a, b, point);
+            false
+        });
+        winding
+    }
+
+    /// Test if `point` is inside outline using crossing number rule.
+    /// See [`Outline::contains`].
+    pub fn contains(&self, point: P) -> bool {
+        FillRule::EvenOdd.apply(self.winding_number(point))
+    }
+
+    /// Location of `point` relative to outline. See [`Outline::locate`].
+    pub fn locate(&self, point: P, rule: FillRule, tolerance: P::Scalar) -> Location {
+        let (p, t) = (xy(point), tolerance.to_f64().max(0f64));
+        let mut winding = 0;
+        let mut boundary = false;
+        let enter =
+            |b: &Bounds| b.min[1] - t <= p[1] && p[1] <= b.max[1] + t && p[0] <= b.max[0] + t;
+        self.visit(enter, |e| {
+            let (a, b) = self.edge(e);
+            boundary = near(a, b, point, t);
+            winding += winding_delta(a, b, point);
+            boundary
+        });
+        if boundary {
+            Location::OnBoundary
+        } else if rule.apply(winding) {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Locations of every point in order of `points`. See [`Outline::locate`].
+    pub fn locate_all(&self, points: &[P], rule: FillRule, tolerance: P::Scalar) -> Vec<Location> {
+        points
+            .iter()
+            .map(|&p| self.locate(p, rule, tolerance))
+            .collect()
+    }
+
+    /// Index of edge nearest to `point` and distance to it. Edge with the least index is
+    /// returned if several are equally near. `None` for empty outline.
+    pub fn nearest_edge(&self, point: P) -> Option<(usize, f64)> {
+        let p = xy(point);
+        let mut best: Option<(usize, f64)> = None;
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![(0, 0f64)]
+        };
+        while let Some((node, distance)) = stack.pop() {
+            if best.map_or(false, |(_, d)| distance > d) {
+                continue;
+            }
+            match &self.nodes[node] {
+                Node::Leaf(_, range) => {
+                    for &e in &self.edges[range.clone()] {
+                        let (a, b) = self.edge(e);
+                        let (_, d) = closest_on_segment(xy(a), xy(b), p);
+                        let better = match best {
+                            Some((i, best)) => d < best || (d == best && e < i),
+                            None => true,
+                        };
+                        if better {
+                            best = Some((e, d));
+                        }
+                    }
+                }
+                Node::Branch(_, left, right) => {
+                    let distance = |n: usize| self.nodes[n].bounds().distance_squared(p);
+                    let (near, far) = ((*left, distance(*left)), (*right, distance(*right)));
+                    // Nearer child is visited first.
+                    if near.1 <= far.1 {
+                        stack.extend_from_slice(&[far, near]);
+                    } else {
+                        stack.extend_from_slice(&[near, far]);
+                    }
+                }
+            }
+        }
+        best.map(|(e, d)| (e, d.sqrt()))
+    }
+
+    /// Indices of edges, which have common point with segment from `a` to `b`, in increasing
+    /// order.
+    pub fn segment_intersections(&self, a: P, b: P) -> Vec<usize> {
+        let segment = Bounds::new(xy(a), xy(b));
+        let mut found = Vec::new();
+        self.visit(
+            |bounds| bounds.overlaps(&segment),
+            |e| {
+                let (c, d) = self.edge(e);
+                if intersection(a, b, c, d).is_some() {
+                    found.push(e);
+                }
+                false
+            },
+        );
+        found.sort_unstable();
+        found
+    }
+
+    /// Test if segment from `a` to `b` has common point with some edge.
+    pub fn intersects_segment(&self, a: P, b: P) -> bool {
+        let segment = Bounds::new(xy(a), xy(b));
+        let mut found = false;
+        self.visit(
+            |bounds| bounds.overlaps(&segment),
+            |e| {
+                let (c, d) = self.edge(e);
+                found = intersection(a, b, c, d).is_some();
+                found
+            },
+        );
+        found
+    }
+}
+
+impl<P: Point> From<Outline<P>> for PreparedOutline<P> {
+    fn from(outline: Outline<P>) -> Self {
+        PreparedOutline::new(outline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PreparedOutline;
+    use crate::location::{FillRule, Location};
+    use crate::outline::Outline;
+    use crate::testing::Random;
+    use crate::validation::intersection;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn queries() {
+        let square = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        let prepared = PreparedOutline::new(square);
+        let locate = |x, y| prepared.locate(Vec2::new(x, y), FillRule::NonZero, 0.1f32);
+        assert_eq!(locate(1f32, 1f32), Location::Inside);
+        assert_eq!(locate(1f32, 2.05f32), Location::OnBoundary);
+        assert_eq!(locate(1f32, 3f32), Location::Outside);
+        assert!(prepared.contains(Vec2::new(1f32, 1f32)));
+
+        assert_eq!(
+            prepared.nearest_edge(Vec2::new(3f32, 1f32)),
+            Some((1, 1f64))
+        );
+        // Corner is equally near to edges 1 and 2.
+        assert_eq!(prepared.nearest_edge(Vec2::new(3f32, 3f32)).unwrap().0, 1);
+
+        let (a, b) = (Vec2::new(-1f32, 1f32), Vec2::new(3f32, 1f32));
+        assert_eq!(prepared.segment_intersections(a, b), vec![1, 3]);
+        assert!(prepared.intersects_segment(a, b));
+        assert!(!prepared.intersects_segment(a, Vec2::new(-1f32, 3f32)));
+
+        let empty = PreparedOutline::new(Outline::new(Vec::<Vec2>::new().into_iter()));
+        assert_eq!(empty.nearest_edge(Vec2::new(0f32, 0f32)), None);
+    }
+
+    #[test]
+    fn random_against_linear() {
+        let mut random = Random(0xb0a7);
+        for _ in 0..30 {
+            let len = 3 + random.next(60) as usize;
+            let points: Vec<_> = (0..len)
+                .map(|_| (random.next(20), random.next(20)))
+                .collect();
+            let ring = outline(&points);
+            let prepared = PreparedOutline::new(ring.clone());
+            for _ in 0..100 {
+                let mut point = || Vec2::new(random.next(44) * 0.5f32, random.next(44) * 0.5f32);
+                let (p, q) = (point(), point());
+                assert_eq!(prepared.winding_number(p), ring.winding_number(p));
+                for &tolerance in &[0f32, 0.7f32] {
+                    let rule = FillRule::EvenOdd;
+                    assert_eq!(
+                        prepared.locate(p, rule, tolerance),
+                        ring.locate(p, rule, tolerance)
+                    );
+                }
+
+                let distance = |(a, b): (Vec2, Vec2)| {
+                    let t = ((p - a).dot(b - a) / (b - a).length_squared()).clamp(0f32, 1f32);
+                    f64::from((a + (b - a) * t - p).length())
+                };
+                let nearest = ring.edges().map(distance).fold(f64::INFINITY, f64::min);
+                let (e, d) = prepared.nearest_edge(p).unwrap();
+                assert!((d - nearest).abs() < 1e-5f64);
+                assert!(
+                    (distance((ring[e as isize], ring[e as isize + 1])) - nearest).abs() < 1e-5f64
+                );
+
+                let crossed: Vec<usize> = ring
+                    .edges()
+                    .enumerate()
+                    .filter(|&(_, (c, d))| intersection(p, q, c, d).is_some())
+                    .map(|(i, _)| i)
+                    .collect();
+                assert_eq!(prepared.segment_intersections(p, q), crossed);
+                assert_eq!(prepared.intersects_segment(p, q), !crossed.is_empty());
+            }
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Repair of invalid outlines.
+//!
+//! [`Outline::repair`] removes non-finite vertices, snaps near-coincident vertices, removes
+//! duplicate and collinear vertices, fixes orientation and finally splits self-intersecting
+//! ring into simple ones by nonzero winding rule.
+use crate::outline::{Orientation, Outline};
+use crate::overlay::overlay;
+use crate::point::Point;
+use crate::polygon::Polygon;
+use crate::validation::OutlineIssue;
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+
+/// Change made by [`Outline::repair`]. Indices refer to vertices of original outline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepairAction {
+    /// Vertex with specified index had infinite or NaN coordinate and was removed.
+    RemovedNonFinite(usize),
+    /// Vertex `vertex` was moved to position `to` of earlier vertex within snap distance.
+    Snapped {
+        /// Index of moved vertex.
+        vertex: usize,
+        /// New position of vertex.
+        to: Vec2,
+    },
+    /// Vertex with specified index was equal to previous one and was removed.
+    RemovedDuplicate(usize),
+    /// Vertex with specified index was on straight line with its neighbors or outline turned
+    /// back at it, so it was removed.
+    RemovedCollinear(usize),
+    /// Clockwise outline was reversed.
+    Reversed,
+    /// Self-intersecting outline was split into specified number of polygons.
+    Split(usize),
+    /// Nothing with non-zero area remained, so outline was dropped.
+    Dropped,
+}
+
+/// Result of [`Outline::repair`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    polygons: Vec<Polygon>,
+    log: Vec<RepairAction>,
+}
+
+impl Repair {
+    /// Valid polygons covering the region of repaired outline. Holes appear only where
+    /// outline winds around region without covering it.
+    pub fn polygons(&self) -> &[Polygon] {
+        &self.polygons
+    }
+
+    /// Changes in order they were made. Empty if outline was already valid.
+    pub fn log(&self) -> &[RepairAction] {
+        &self.log
+    }
+
+    /// Splits result into polygons and log.
+    pub fn into_parts(self) -> (Vec<Polygon>, Vec<RepairAction>) {
+        (self.polygons, self.log)
+    }
+}
+
+/// Moves every vertex closer than `distance` to earlier unmoved vertex onto it.
+fn snap(vertices: &mut [(usize, Vec2)], distance: f32, log: &mut Vec<RepairAction>) {
+    if distance <= 0f32 {
+        return;
+    }
+    let cell = |p: Vec2| {
+        let c = p / distance;
+        (c.x().floor() as i64, c.y().floor() as i64)
+    };
+    let mut anchors: HashMap<(i64, i64), Vec<Vec2>> = HashMap::new();
+    for (i, p) in vertices.iter_mut() {
+        let (x, y) = cell(*p);
+        let near = (x - 1..=x + 1)
+            .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+            .filter_map(|key| anchors.get(&key))
+            .flatten()
+            .copied()
+            .filter(|&anchor| (anchor - *p).length() < distance)
+            .min_by(|&a, &b| {
+                let (a, b) = ((a - *p).length(), (b - *p).length());
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            });
+        match near {
+            Some(anchor) if anchor != *p => {
+                *p = anchor;
+                log.push(RepairAction::Snapped {
+                    vertex: *i,
+                    to: anchor,
+                });
+            }
+            Some(_) => {}
+            None => anchors.entry((x, y)).or_default().push(*p),
+        }
+    }
+}
+
+/// Removes duplicate, collinear and spike vertices until none remain. Stops when less than
+/// three vertices left.
+fn simplify(ring: Vec<(usize, Vec2)>, log: &mut Vec<RepairAction>) -> Vec<(usize, Vec2)> {
+    let len = ring.len();
+    let mut prev: Vec<usize> = (0..len).map(|i| (i + len - 1) % len).collect();
+    let mut next: Vec<usize> = (0..len).map(|i| (i + 1) % len).collect();
+    let mut alive = vec![true; len];
+    let mut count = len;
+    // Neighbors of removed vertex are checked again.
+    let mut pending: VecDeque<usize> = (0..len).collect();
+    while let Some(i) = pending.pop_front() {
+        if count < 3 {
+            break;
+        }
+        if !alive[i] {
+            continue;
+        }
+        let (p, n) = (prev[i], next[i]);
+        let (index, that) = ring[i];
+        let action = if ring[p].1 == that {
+            RepairAction::RemovedDuplicate(index)
+        } else if ring[n].1 != that && Vec2::orient(ring[p].1, that, ring[n].1) == Ordering::Equal {
+            RepairAction::RemovedCollinear(index)
+        } else {
+            continue;
+        };
+        log.push(action);
+        alive[i] = false;
+        count -= 1;
+        next[p] = n;
+        prev[n] = p;
+        pending.push_front(n);
+        pending.push_front(p);
+    }
+    ring.into_iter()
+        .zip(alive)
+        .filter_map(|(v, alive)| if alive { Some(v) } else { None })
+        .collect()
+}
+
+impl Outline {
+    /// Turns outline into valid polygons and reports every change.
+    /// # Arguments
+    /// * `snap_distance` - vertices closer than this distance to earlier vertex are moved
+    ///   onto it. Zero disables snapping;
+    ///
+    /// Clockwise outline is treated as counter-clockwise one and reversed. Self-intersections
+    /// are resolved by nonzero winding rule, so figure-eight becomes two polygons.
+    pub fn repair(&self, snap_distance: f32) -> Repair {
+        let mut log = Vec::new();
+        let mut ring = Vec::with_capacity(self.len());
+        for (i, &p) in self.vertices().iter().enumerate() {
+            if p.x().is_finite() && p.y().is_finite() {
+                ring.push((i, p));
+            } else {
+                log.push(RepairAction::RemovedNonFinite(i));
+            }
+        }
+        snap(&mut ring, snap_distance, &mut log);
+        let ring = simplify(ring, &mut log);
+        if ring.len() < 3 {
+            log.push(RepairAction::Dropped);
+            return Repair {
+                polygons: Vec::new(),
+                log,
+            };
+        }
+
+        let mut outline = Outline::new(ring.into_iter().map(|(_, p)| p));
+        if outline.ensure_ccw() {
+            log.push(RepairAction::Reversed);
+        }
+        let intersects = outline.validate().issues().iter().any(|issue| {
+            matches!(
+                issue,
+                OutlineIssue::SelfIntersection { .. } | OutlineIssue::DuplicateVertex(..)
+            )
+        });
+        let polygons = if intersects || outline.orientation() == Orientation::Degenerate {
+            let segments: Vec<_> = outline.edges().map(|(a, b)| (a, b, 0)).collect();
+            let polygons = overlay(&segments, |winding| winding[0] != 0);
+            if polygons.len() > 1 {
+                log.push(RepairAction::Split(polygons.len()));
+            }
+            polygons
+        } else {
+            Polygon::without_holes(outline).into_iter().collect()
+        };
+        if polygons.is_empty() {
+            log.push(RepairAction::Dropped);
+        }
+        Repair { polygons, log }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RepairAction;
+    use crate::outline::Outline;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn valid_untouched() {
+        let square = outline(&[(0f32, 0f32), (1f32, 0f32), (1f32, 1f32), (0f32, 1f32)]);
+        let repair = square.repair(0.01f32);
+        assert!(repair.log().is_empty());
+        assert_eq!(repair.polygons().len(), 1);
+        assert_eq!(repair.polygons()[0].outline(), &square);
+    }
+
+    #[test]
+    fn cleanup() {
+        let messy = outline(&[
+            (0f32, 0f32),
+            (1f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 2f32),
+            (2f32, 3f32),
+            (2f32, 2f32),
+            (f32::NAN, 1f32),
+            (1f32, 2f32),
+            (0f32, 2f32),
+        ]);
+        let (polygons, log) = messy.repair(0.01f32).into_parts();
+        assert_eq!(
+            log,
+            vec![
+                RepairAction::RemovedNonFinite(7),
+                RepairAction::RemovedCollinear(1),
+                RepairAction::RemovedDuplicate(3),
+                RepairAction::RemovedCollinear(4),
+                RepairAction::RemovedCollinear(5),
+                RepairAction::RemovedCollinear(8),
+            ]
+        );
+        let expected = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        assert_eq!(polygons[0].outline(), &expected);
+        assert!(polygons[0].outline().validate().is_valid());
+    }
+
+    #[test]
+    fn snapping() {
+        // Vertex 4 almost touches bottom edge, snapped it pinches outline into two triangles.
+        let nearly = outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (4f32, 0f32),
+            (4f32, 4f32),
+            (2.005f32, 0.005f32),
+            (0f32, 4f32),
+        ]);
+        let (polygons, log) = nearly.repair(0.01f32).into_parts();
+        assert_eq!(
+            log,
+            vec![
+                RepairAction::Snapped {
+                    vertex: 4,
+                    to: Vec2::new(2f32, 0f32),
+                },
+                RepairAction::RemovedCollinear(1),
+                RepairAction::Split(2),
+            ]
+        );
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons.iter().map(|p| p.area()).sum::<f32>(), 8f32);
+    }
+
+    #[test]
+    fn figure_eight() {
+        // Lobes of equal area are split as is.
+        let figure_eight = outline(&[(0f32, 0f32), (0f32, 2f32), (4f32, 0f32), (4f32, 2f32)]);
+        let (polygons, log) = figure_eight.repair(0f32).into_parts();
+        assert_eq!(log, vec![RepairAction::Split(2)]);
+        assert_eq!(polygons.len(), 2);
+        let area: f32 = polygons.iter().map(|p| p.area()).sum();
+        assert_eq!(area, 4f32);
+        for polygon in &polygons {
+            assert!(polygon.outline().validate().is_valid());
+        }
+
+        // Clockwise dominant lobe is reversed, then lobes are split.
+        let figure_eight = outline(&[(0f32, 0f32), (0f32, 4f32), (4f32, 0f32), (4f32, 2f32)]);
+        let (polygons, log) = figure_eight.repair(0f32).into_parts();
+        assert_eq!(log, vec![RepairAction::Reversed, RepairAction::Split(2)]);
+        assert_eq!(polygons.len(), 2);
+        let area: f32 = polygons.iter().map(|p| p.area()).sum();
+        assert!((area - 20f32 / 3f32).abs() < 1e-5f32);
+        for polygon in &polygons {
+            assert!(polygon.outline().validate().is_valid());
+        }
+
+        let clockwise = outline(&[(0f32, 0f32), (0f32, 1f32), (1f32, 1f32), (1f32, 0f32)]);
+        let repair = clockwise.repair(0f32);
+        assert_eq!(repair.log(), &[RepairAction::Reversed]);
+        assert_eq!(repair.polygons()[0].area(), 1f32);
+    }
+
+    #[test]
+    fn dropped() {
+        let line = outline(&[(0f32, 0f32), (1f32, 1f32), (2f32, 2f32)]);
+        let repair = line.repair(0f32);
+        assert!(repair.polygons().is_empty());
+        assert_eq!(repair.log().last(), Some(&RepairAction::Dropped));
+    }
+
+    #[test]
+    fn touching_hole_is_not_split() {
+        // Clockwise loop touches outer loop at the first vertex and becomes hole.
+        let touching = outline(&[
+            (0f32, 0f32),
+            (4f32, 0f32),
+            (4f32, 4f32),
+            (0f32, 4f32),
+            (0f32, 0f32),
+            (1f32, 2f32),
+            (2f32, 1f32),
+        ]);
+        let (polygons, log) = touching.repair(0f32).into_parts();
+        assert_eq!(log, vec![]);
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].holes().len(), 1);
+        assert_eq!(polygons[0].area(), 16f32 - 1.5f32);
+    }
+}
@@ -0,0 +1,495 @@
+//! Simplification of outlines by Ramer–Douglas–Peucker and Visvalingam–Whyatt algorithms.
+//!
+//! Simplified outline keeps a subset of vertices in their order. Outline **MUST** be simple,
+//! then result is simple too and has the same orientation, as vertices are removed only when
+//! this doesn't change topology.
+use crate::geometry::{closest_on_segment, total_cmp};
+use crate::outline::Outline;
+use crate::point::{Point, Scalar};
+use crate::validation::OutlineIssue;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+type Xy = [f64; 2];
+
+fn xy<P: Point>(p: P) -> Xy {
+    [p.x().to_f64(), p.y().to_f64()]
+}
+
+/// Vertex of `v` between `from` and `to` farthest from segment between them and squared
+/// distance to it. Indices wrap around number of vertices, `from` is less than `to`.
+fn farthest<P: Point>(v: &[P], from: usize, to: usize) -> Option<(usize, f64)> {
+    let n = v.len();
+    let (a, b) = (xy(v[from % n]), xy(v[to % n]));
+    (from + 1..to)
+        .map(|k| (k, closest_on_segment(a, b, xy(v[k % n])).1))
+        .fold(None, |best, (k, d)| match best {
+            Some((_, best_d)) if best_d >= d => best,
+            _ => Some((k, d)),
+        })
+}
+
+/// Keeps vertices of chains, which are farther than `tolerance` from shortcuts of chains.
+fn douglas_peucker<P: Point>(
+    v: &[P],
+    keep: &mut [bool],
+    mut chains: Vec<(usize, usize)>,
+    tolerance: f64,
+) {
+    while let Some((from, to)) = chains.pop() {
+        if let Some((k, d)) = farthest(v, from, to) {
+            if d > tolerance * tolerance {
+                keep[k % v.len()] = true;
+                chains.push((from, k));
+                chains.push((k, to));
+            }
+        }
+    }
+}
+
+/// Vertices in square grid of cells, so vertices near triangle are found fast.
+struct Grid {
+    min: Xy,
+    cell: f64,
+    side: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    fn new(points: &[Xy]) -> Self {
+        let fold =
+            |f: fn(f64, f64) -> f64, k: usize, init: f64| points.iter().map(|p| p[k]).fold(init, f);
+        let min = [
+            fold(f64::min, 0, f64::INFINITY),
+            fold(f64::min, 1, f64::INFINITY),
+        ];
+        let max = [
+            fold(f64::max, 0, -f64::INFINITY),
+            fold(f64::max, 1, -f64::INFINITY),
+        ];
+        let side = (points.len() as f64).sqrt().ceil().max(1f64) as usize;
+        let extent = (max[0] - min[0]).max(max[1] - min[1]);
+        let cell = if extent > 0f64 {
+            extent / side as f64
+        } else {
+            1f64
+        };
+        let mut grid = Grid {
+            min,
+            cell,
+            side,
+            cells: vec![Vec::new(); side * side],
+        };
+        for (i, &p) in points.iter().enumerate() {
+            let cell = grid.cell_of(p);
+            grid.cells[cell[1] * side + cell[0]].push(i);
+        }
+        grid
+    }
+
+    fn cell_of(&self, p: Xy) -> [usize; 2] {
+        let index = |k: usize| {
+            let i = ((p[k] - self.min[k]) / self.cell).floor();
+            (i.max(0f64) as usize).min(self.side - 1)
+        };
+        [index(0), index(1)]
+    }
+
+    fn remove(&mut self, i: usize, p: Xy) {
+        let cell = self.cell_of(p);
+        self.cells[cell[1] * self.side + cell[0]].retain(|&j| j != i);
+    }
+
+    /// Vertices in cells overlapping box from `min` to `max`.
+    fn query(&self, min: Xy, max: Xy) -> impl Iterator<Item = usize> + '_ {
+        let (from, to) = (self.cell_of(min), self.cell_of(max));
+        (from[1]..=to[1]).flat_map(move |y| {
+            (from[0]..=to[0]).flat_map(move |x| self.cells[y * self.side + x].iter().copied())
+        })
+    }
+}
+
+/// Test if `p` lies inside of triangle (`a`, `b`, `c`) of any orientation or on its border.
+fn in_closed_triangle<P: Point>(a: P, b: P, c: P, p: P) -> bool {
+    let sides = [P::orient(a, b, p), P::orient(b, c, p), P::orient(c, a, p)];
+    let between = |a: P::Scalar, b: P::Scalar, c: P::Scalar, p: P::Scalar| {
+        let less = |x: P::Scalar| x <= p;
+        let greater = |x: P::Scalar| x >= p;
+        (less(a) || less(b) || less(c)) && (greater(a) || greater(b) || greater(c))
+    };
+    // Bounds matter for degenerate triangle only.
+    !(sides.contains(&Ordering::Less) && sides.contains(&Ordering::Greater))
+        && between(a.x(), b.x(), c.x(), p.x())
+        && between(a.y(), b.y(), c.y(), p.y())
+}
+
+/// Vertex in queue of Visvalingam–Whyatt algorithm. The least area is the greatest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    area: f64,
+    vertex: usize,
+    version: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .partial_cmp(&self.area)
+            .unwrap()
+            .then(other.vertex.cmp(&self.vertex))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Point> Outline<P> {
+    /// Simplified outline by Ramer–Douglas–Peucker algorithm. Every removed vertex is not
+    /// farther than `tolerance` from edge, which replaces it. Where shortcuts would intersect
+    /// other edges or turn outline over, removed vertices are restored. Outline **MUST** be
+    /// simple.
+    /// # Arguments
+    /// * `tolerance` - the greatest distance from removed vertex to simplified outline;
+    pub fn simplify_by_distance(&self, tolerance: P::Scalar) -> Outline<P> {
+        if self.len() <= 3 {
+            return self.clone();
+        }
+        let v = self.vertices();
+        let n = v.len();
+        let tolerance = tolerance.to_f64();
+        let zero = P::Area::zero();
+        let orientation = self
+            .doubled_signed_area()
+            .partial_cmp(&zero)
+            .unwrap_or(Ordering::Equal);
+
+        // Ring is split at the first vertex and the farthest from it.
+        let origin = xy(v[0]);
+        let distance = |k: usize| {
+            let p = xy(v[k]);
+            (p[0] - origin[0]).hypot(p[1] - origin[1])
+        };
+        let split = (1..n)
+            .max_by(|&i, &j| total_cmp(distance(i), distance(j)).then(j.cmp(&i)))
+            .unwrap();
+        let mut keep = vec![false; n];
+        keep[0] = true;
+        keep[split] = true;
+        douglas_peucker(v, &mut keep, vec![(0, split), (split, n)], tolerance);
+
+        loop {
+            let kept: Vec<usize> = (0..n).filter(|&i| keep[i]).collect();
+            let chain = |e: usize| match kept.get(e + 1) {
+                Some(&to) => (kept[e], to),
+                None => (kept[e], kept[0] + n),
+            };
+            let simplified = Outline::new(kept.iter().map(|&i| v[i]));
+            let mut conflicts = Vec::new();
+            let issues = if kept.len() >= 3 {
+                simplified.validate().into_issues()
+            } else {
+                Vec::new()
+            };
+            for issue in issues {
+                match issue {
+                    OutlineIssue::Spike(i) => {
+                        conflicts.push((i + kept.len() - 1) % kept.len());
+                        conflicts.push(i);
+                    }
+                    OutlineIssue::SelfIntersection { edges, .. } => {
+                        conflicts.push(edges.0);
+                        conflicts.push(edges.1);
+                    }
+                    _ => {}
+                }
+            }
+            // Only shortcuts can conflict, as edges of simple outline don't.
+            conflicts.retain(|&e| {
+                let (from, to) = chain(e);
+                to - from > 1
+            });
+            let same_orientation =
+                simplified.doubled_signed_area().partial_cmp(&zero) == Some(orientation);
+            if kept.len() >= 3 && conflicts.is_empty() && same_orientation {
+                return simplified;
+            }
+            if conflicts.is_empty() {
+                // Outline is turned over or degenerate, so the farthest vertex is restored.
+                let deviation = |e: usize| {
+                    let (from, to) = chain(e);
+                    farthest(v, from, to).map_or(-1f64, |(_, d)| d)
+                };
+                let worst = (0..kept.len())
+                    .max_by(|&e, &f| total_cmp(deviation(e), deviation(f)))
+                    .unwrap();
+                conflicts.push(worst);
+            }
+            conflicts.sort_unstable();
+            conflicts.dedup();
+            for e in conflicts {
+                let (from, to) = chain(e);
+                if let Some((k, _)) = farthest(v, from, to) {
+                    keep[k % n] = true;
+                    // Vertices of both parts must be within tolerance again.
+                    douglas_peucker(v, &mut keep, vec![(from, k), (k, to)], tolerance);
+                }
+            }
+        }
+    }
+
+    /// Simplified outline by Visvalingam–Whyatt algorithm. Vertex forming triangle of the least
+    /// area with its neighbors is removed first, while such area is less than `area`. Vertex
+    /// isn't removed, if its triangle contains other vertices, so result may have more vertices
+    /// than tolerance allows. Outline **MUST** be simple.
+    /// # Arguments
+    /// * `area` - removed vertices form triangles with less area, than this;
+    pub fn simplify_by_area(&self, area: P::Area) -> Outline<P> {
+        self.visvalingam(area.to_f64(), 3)
+    }
+
+    /// Simplified outline with `len` vertices by Visvalingam–Whyatt algorithm. Result has more
+    /// vertices, if removing of any vertex would change topology, or if `len` is less than
+    /// three. See [`Outline::simplify_by_area`].
+    pub fn simplify_to_count(&self, len: usize) -> Outline<P> {
+        self.visvalingam(f64::INFINITY, len.max(3))
+    }
+
+    fn visvalingam(&self, tolerance: f64, min_len: usize) -> Outline<P> {
+        if self.len() <= 3 {
+            return self.clone();
+        }
+        let v = self.vertices();
+        let n = v.len();
+        let points: Vec<Xy> = v.iter().map(|&p| xy(p)).collect();
+        let mut grid = Grid::new(&points);
+        let mut prev: Vec<usize> = (0..n).map(|i| (i + n - 1) % n).collect();
+        let mut next: Vec<usize> = (0..n).map(|i| (i + 1) % n).collect();
+        let mut removed = vec![false; n];
+        let mut version = vec![0; n];
+        let area = |a: usize, b: usize, c: usize| {
+            let (a, b, c) = (points[a], points[b], points[c]);
+            ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() * 0.5f64
+        };
+        let mut queue: BinaryHeap<Candidate> = (0..n)
+            .map(|i| Candidate {
+                area: area(prev[i], i, next[i]),
+                vertex: i,
+                version: 0,
+            })
+            .collect();
+
+        let mut len = n;
+        // Removed area only grows, so vertices are removed in order of significance.
+        let mut last_area = 0f64;
+        let mut blocked: Vec<usize> = Vec::new();
+        let mut progress = false;
+        while len > min_len {
+            let candidate = loop {
+                match queue.pop() {
+                    Some(c) if removed[c.vertex] || c.version != version[c.vertex] => {}
+                    other => break other.filter(|c| c.area < tolerance),
+                }
+            };
+            let candidate = match candidate {
+                Some(candidate) => candidate,
+                None if progress && !blocked.is_empty() => {
+                    // Removed vertices may have unblocked others, so they are tried again.
+                    for i in blocked.drain(..) {
+                        if !removed[i] {
+                            version[i] += 1;
+                            let area = area(prev[i], i, next[i]).max(last_area);
+                            queue.push(Candidate {
+                                area,
+                                vertex: i,
+                                version: version[i],
+                            });
+                        }
+                    }
+                    progress = false;
+                    continue;
+                }
+                None => break,
+            };
+            let i = candidate.vertex;
+            let (a, b) = (prev[i], next[i]);
+            let (min, max) = (
+                [
+                    points[a][0].min(points[i][0]).min(points[b][0]),
+                    points[a][1].min(points[i][1]).min(points[b][1]),
+                ],
+                [
+                    points[a][0].max(points[i][0]).max(points[b][0]),
+                    points[a][1].max(points[i][1]).max(points[b][1]),
+                ],
+            );
+            // Empty triangle keeps outline simple and its orientation unchanged, except for
+            // collinear vertices left.
+            let occupied = grid
+                .query(min, max)
+                .any(|j| j != a && j != i && j != b && in_closed_triangle(v[a], v[i], v[b], v[j]));
+            let degenerate = len == 4 && P::orient(v[a], v[b], v[next[b]]) == Ordering::Equal;
+            if occupied || degenerate {
+                blocked.push(i);
+                continue;
+            }
+            removed[i] = true;
+            grid.remove(i, points[i]);
+            next[a] = b;
+            prev[b] = a;
+            len -= 1;
+            progress = true;
+            last_area = last_area.max(candidate.area);
+            for &j in &[a, b] {
+                version[j] += 1;
+                queue.push(Candidate {
+                    area: area(prev[j], j, next[j]).max(last_area),
+                    vertex: j,
+                    version: version[j],
+                });
+            }
+        }
+        Outline::new((0..n).filter(|&i| !removed[i]).map(|i| v[i]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::outline::Outline;
+    use crate::testing::Random;
+    use crate::validation::OutlineIssue;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    /// Test if outline is simple and vertices of `simplified` are subsequence of `original`.
+    fn check(original: &Outline, simplified: &Outline) {
+        let issues = simplified.validate().into_issues();
+        assert!(
+            issues
+                .iter()
+                .all(|issue| matches!(issue, OutlineIssue::WrongOrientation(_))),
+            "{:?}",
+            issues
+        );
+        assert_eq!(simplified.orientation(), original.orientation());
+        let mut rest = original.vertices().iter();
+        for p in simplified.vertices() {
+            assert!(rest.any(|q| q == p));
+        }
+    }
+
+    /// Circle of 200 vertices with small alternating bumps.
+    fn noisy_circle() -> Outline {
+        Outline::new((0..200).map(|i| {
+            let angle = i as f32 / 200f32 * std::f32::consts::PI * 2f32;
+            let radius = 10f32 + if i % 2 == 0 { 0.05f32 } else { -0.05f32 };
+            Vec2::new(angle.cos(), angle.sin()) * radius
+        }))
+    }
+
+    #[test]
+    fn by_distance() {
+        let circle = noisy_circle();
+        let simplified = circle.simplify_by_distance(0.2f32);
+        check(&circle, &simplified);
+        assert!(simplified.len() < 40);
+        for &p in circle.vertices() {
+            assert!(simplified.closest_point(p).unwrap().signed_distance.abs() <= 0.2f32);
+        }
+        // Huge tolerance leaves triangle.
+        assert_eq!(circle.simplify_by_distance(100f32).len(), 3);
+        let triangle = outline(&[(0f32, 0f32), (1f32, 0f32), (0f32, 1f32)]);
+        assert_eq!(triangle.simplify_by_distance(10f32), triangle);
+    }
+
+    #[test]
+    fn by_area() {
+        // Square with extra vertices on its sides.
+        let square = outline(&[
+            (0f32, 0f32),
+            (1f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 1f32),
+            (2f32, 2f32),
+            (1f32, 2f32),
+            (0f32, 2f32),
+            (0f32, 1f32),
+        ]);
+        let expected = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        assert_eq!(square.simplify_by_area(1e-3f32), expected);
+        assert_eq!(square.simplify_to_count(4), expected);
+
+        let circle = noisy_circle();
+        let simplified = circle.simplify_to_count(20);
+        check(&circle, &simplified);
+        assert_eq!(simplified.len(), 20);
+        assert_eq!(circle.simplify_to_count(0).len(), 3);
+    }
+
+    #[test]
+    fn keeps_topology() {
+        // Narrow comb: shortcuts of wavy teeth would cross neighbor teeth.
+        let mut points = vec![(0f32, 0f32), (9f32, 0f32)];
+        for tooth in (0..3).rev() {
+            let x = tooth as f32 * 3f32;
+            points.push((x + 2f32, 10f32));
+            points.push((x + 1.5f32, 5f32));
+            points.push((x + 1f32, 10f32));
+            points.push((x + 0.9f32, 0.5f32));
+        }
+        points.pop();
+        let comb = outline(&points);
+        assert!(comb.validate().is_valid());
+        for &tolerance in &[0.5f32, 2f32, 6f32, 20f32] {
+            check(&comb, &comb.simplify_by_distance(tolerance));
+            check(&comb, &comb.simplify_by_area(tolerance * tolerance));
+        }
+        for len in 0..comb.len() {
+            check(&comb, &comb.simplify_to_count(len));
+        }
+
+        // Vertex inside of triangle of the least area blocks its removal.
+        let blocked = outline(&[
+            (0f32, 0f32),
+            (4f32, 0f32),
+            (4f32, 4f32),
+            (2.1f32, 0.2f32),
+            (2f32, 4f32),
+            (0f32, 4f32),
+        ]);
+        check(&blocked, &blocked.simplify_to_count(3));
+    }
+
+    #[test]
+    fn random_stars() {
+        let mut random = Random(0x51e);
+        for round in 0..100 {
+            // Outline with vertices sorted by angle around center is simple, if angle between
+            // neighbor vertices is less than straight.
+            let len = random.next(60) as usize;
+            let mut angles: Vec<f32> = (0..len).map(|_| random.next(1000)).collect();
+            angles.extend_from_slice(&[0f32, 333f32, 666f32]);
+            angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            angles.dedup();
+            let mut star = Outline::new(angles.iter().map(|&a| {
+                let angle = a / 1001f32 * std::f32::consts::PI * 2f32;
+                Vec2::new(angle.cos(), angle.sin()) * (1f32 + random.next(8))
+            }));
+            if round % 2 == 1 {
+                star.reverse();
+            }
+            let tolerance = random.next(30) * 0.1f32;
+            check(&star, &star.simplify_by_distance(tolerance));
+            check(&star, &star.simplify_by_area(tolerance * tolerance));
+            check(&star, &star.simplify_to_count(random.next(20) as usize));
+        }
+    }
+}
@@ -0,0 +1,684 @@
+use crate::geometry::{cmp_turn, cross, total_cmp};
+use crate::outline::Outline;
+use crate::polygon::Polygon;
+use glam::{Vec2, Vec3};
+
+/// Node of straight skeleton
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkeletonNode {
+    /// Position of node.
+    pub position: Vec2,
+    /// Offset distance, at which wavefront reaches node. Zero for vertices of source shape.
+    pub time: f32,
+}
+
+/// Straight skeleton: graph traced by vertices of shape while its edges move inside with unit
+/// speed
+#[derive(Debug, Clone, PartialEq)]
+pub struct StraightSkeleton {
+    nodes: Vec<SkeletonNode>,
+    arcs: Vec<(usize, usize)>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl Outline {
+    /// Straight skeleton of outline.
+    pub fn straight_skeleton(&self) -> StraightSkeleton {
+        Wavefront::new(std::iter::once(self)).run()
+    }
+}
+
+impl Polygon {
+    /// Straight skeleton of polygon with holes.
+    pub fn straight_skeleton(&self) -> StraightSkeleton {
+        Wavefront::new(self.rings()).run()
+    }
+}
+
+impl StraightSkeleton {
+    /// Nodes of skeleton. Starts with vertices of source shape in their order.
+    pub fn nodes(&self) -> &[SkeletonNode] {
+        &self.nodes
+    }
+
+    /// Arcs as pairs of indices into [`StraightSkeleton::nodes`]. First node of arc is reached
+    /// by wavefront earlier than second.
+    pub fn arcs(&self) -> &[(usize, usize)] {
+        &self.arcs
+    }
+
+    /// Faces of hip roof: one face for every edge of source shape, in order of edges.
+    /// Face starts with ends of edge and follows counter-clockwise. Height of vertex is its
+    /// time multiplied by `slope`.
+    pub fn roof_faces(&self, slope: f32) -> Vec<Vec<Vec3>> {
+        let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+        for &(a, b) in &self.arcs {
+            neighbors[a].push(b);
+            neighbors[b].push(a);
+        }
+        for &(a, b) in &self.edges {
+            neighbors[a].push(b);
+        }
+        let mut faces = Vec::with_capacity(self.edges.len());
+        for &(start, second) in &self.edges {
+            let mut face = vec![start];
+            let (mut from, mut to) = (start, second);
+            while to != start && face.len() <= self.nodes.len() {
+                face.push(to);
+                // Inner area of face is at the left, so take the first neighbor clockwise
+                // from the reversed incoming direction.
+                let position = |n: usize| self.nodes[n].position;
+                let next = neighbors[to]
+                    .iter()
+                    .copied()
+                    .min_by(|&x, &y| {
+                        // Going back is the last resort.
+                        (x == from).cmp(&(y == from)).then_with(|| {
+                            let (p, q) = (position(x), position(y));
+                            cmp_turn(position(to), position(from), p, q, true)
+                        })
+                    })
+                    .unwrap_or(from);
+                from = to;
+                to = next;
+            }
+            faces.push(
+                face.into_iter()
+                    .map(|n| {
+                        let node = self.nodes[n];
+                        node.position.extend(node.time * slope)
+                    })
+                    .collect(),
+            );
+        }
+        faces
+    }
+}
+
+/// Edge of source shape.
+struct SourceEdge {
+    from: Vec2,
+    to: Vec2,
+    dir: Vec2,
+    /// Unit normal pointing inside.
+    normal: Vec2,
+}
+
+/// Active vertex of wavefront. Moves from `origin` with `velocity` since `time`.
+#[derive(Clone)]
+struct Vertex {
+    origin: Vec2,
+    time: f32,
+    velocity: Vec2,
+    prev: usize,
+    next: usize,
+    /// Source edge between `prev` and this vertex.
+    edge_in: usize,
+    /// Source edge between this vertex and `next`.
+    edge_out: usize,
+    node: usize,
+    alive: bool,
+}
+
+impl Vertex {
+    fn position(&self, time: f32) -> Vec2 {
+        self.origin + self.velocity * (time - self.time)
+    }
+}
+
+enum Event {
+    /// Edge between vertex and its next neighbor shrinks to point.
+    Edge(usize),
+    /// Reflex vertex hits edge between vertex `.1` and its next neighbor.
+    Split(usize, usize),
+}
+
+/// Simulation of wavefront propagation. Earliest event is searched among all active vertices
+/// at every step, so it takes `O(n^3)` time in worst case.
+struct Wavefront {
+    edges: Vec<SourceEdge>,
+    vertices: Vec<Vertex>,
+    nodes: Vec<SkeletonNode>,
+    arcs: Vec<(usize, usize)>,
+    source: Vec<(usize, usize)>,
+    now: f32,
+    epsilon: f32,
+}
+
+impl Wavefront {
+    fn new<'a>(rings: impl Iterator<Item = &'a Outline>) -> Self {
+        let mut wavefront = Wavefront {
+            edges: Vec::new(),
+            vertices: Vec::new(),
+            nodes: Vec::new(),
+            arcs: Vec::new(),
+            source: Vec::new(),
+            now: 0f32,
+            epsilon: 0f32,
+        };
+        let mut extent = 0f32;
+        for ring in rings {
+            let start = wavefront.vertices.len();
+            let len = ring.len();
+            for (i, (from, to)) in ring.edges().enumerate() {
+                let dir = (to - from).normalize();
+                wavefront.edges.push(SourceEdge {
+                    from,
+                    to,
+                    dir,
+                    normal: Vec2::new(-dir.y(), dir.x()),
+                });
+                wavefront.nodes.push(SkeletonNode {
+                    position: from,
+                    time: 0f32,
+                });
+                wavefront.source.push((start + i, start + (i + 1) % len));
+                extent = extent.max(from.x().abs()).max(from.y().abs());
+            }
+            for i in 0..len {
+                let vertex = start + i;
+                wavefront.vertices.push(Vertex {
+                    origin: ring[i as isize],
+                    time: 0f32,
+                    velocity: Vec2::zero(),
+                    prev: start + (i + len - 1) % len,
+                    next: start + (i + 1) % len,
+                    edge_in: start + (i + len - 1) % len,
+                    edge_out: vertex,
+                    node: vertex,
+                    alive: true,
+                });
+            }
+        }
+        wavefront.epsilon = extent.max(1f32) * 1e-5f32;
+        for v in 0..wavefront.vertices.len() {
+            wavefront.vertices[v].velocity = wavefront.velocity(v);
+        }
+        wavefront
+    }
+
+    /// Velocity of vertex, that keeps it on both adjacent moving edges.
+    fn velocity(&self, v: usize) -> Vec2 {
+        let vertex = &self.vertices[v];
+        let n1 = self.edges[vertex.edge_in].normal;
+        let n2 = self.edges[vertex.edge_out].normal;
+        let det = n1.perp_dot(n2);
+        if det.abs() <= 1e-6f32 {
+            // Collinear edges move together, opposite edges have met.
+            return if n1.dot(n2) > 0f32 { n1 } else { Vec2::zero() };
+        }
+        Vec2::new(n2.y() - n1.y(), n1.x() - n2.x()) / det
+    }
+
+    fn is_reflex(&self, v: usize) -> bool {
+        let vertex = &self.vertices[v];
+        let (e1, e2) = (&self.edges[vertex.edge_in], &self.edges[vertex.edge_out]);
+        cross(e1.from, e1.to, e2.from, e2.to) < 0f64
+    }
+
+    fn add_node(&mut self, position: Vec2, time: f32) -> usize {
+        self.nodes.push(SkeletonNode { position, time });
+        self.nodes.len() - 1
+    }
+
+    /// Deactivates vertex and connects its node with `node`.
+    fn finish(&mut self, v: usize, node: usize) {
+        self.vertices[v].alive = false;
+        let from = self.vertices[v].node;
+        if from != node {
+            self.arcs.push((from, node));
+        }
+    }
+
+    fn edge_event_time(&self, a: usize) -> Option<f32> {
+        let va = &self.vertices[a];
+        let vb = &self.vertices[va.next];
+        let dir = self.edges[va.edge_out].dir;
+        let length = (vb.position(self.now) - va.position(self.now)).dot(dir);
+        if length <= self.epsilon {
+            return Some(self.now);
+        }
+        let shrink = (va.velocity - vb.velocity).dot(dir);
+        if shrink <= 0f32 {
+            return None;
+        }
+        Some(self.now + length / shrink)
+    }
+
+    fn split_event_time(&self, r: usize, u: usize) -> Option<f32> {
+        let vr = &self.vertices[r];
+        let vu = &self.vertices[u];
+        let w = vu.next;
+        if u == r || w == r || vr.prev == u {
+            return None;
+        }
+        let edge = &self.edges[vu.edge_out];
+        // Distance from vertex to moving edge decreases with speed `approach`.
+        let distance = (vr.position(self.now) - edge.from).dot(edge.normal) - self.now;
+        let approach = 1f32 - vr.velocity.dot(edge.normal);
+        if distance < -self.epsilon || approach <= 0f32 {
+            return None;
+        }
+        let time = self.now + distance.max(0f32) / approach;
+        let hit = vr.position(time);
+        let start = (vu.position(time) - edge.from).dot(edge.dir);
+        let end = (self.vertices[w].position(time) - edge.from).dot(edge.dir);
+        let along = (hit - edge.from).dot(edge.dir);
+        if end - start > self.epsilon && along > start - self.epsilon && along < end + self.epsilon
+        {
+            Some(time)
+        } else {
+            None
+        }
+    }
+
+    fn next_event(&self) -> Option<(f32, Event)> {
+        let mut best: Option<(f32, Event)> = None;
+        let alive: Vec<usize> = (0..self.vertices.len())
+            .filter(|&v| self.vertices[v].alive)
+            .collect();
+        for &a in &alive {
+            if let Some(time) = self.edge_event_time(a) {
+                if best.as_ref().map_or(true, |(t, _)| time < *t) {
+                    best = Some((time, Event::Edge(a)));
+                }
+            }
+        }
+        for &r in alive.iter().filter(|&&r| self.is_reflex(r)) {
+            for &u in &alive {
+                if let Some(time) = self.split_event_time(r, u) {
+                    // Edge events win ties, so simultaneous collapses stay consistent.
+                    if best
+                        .as_ref()
+                        .map_or(true, |(t, _)| time < *t - self.epsilon)
+                    {
+                        best = Some((time, Event::Split(r, u)));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn spawn(&mut self, vertex: Vertex) -> usize {
+        self.vertices.push(vertex);
+        let v = self.vertices.len() - 1;
+        self.vertices[v].velocity = self.velocity(v);
+        v
+    }
+
+    fn edge_event(&mut self, a: usize, time: f32) {
+        let b = self.vertices[a].next;
+        let position = (self.vertices[a].position(time) + self.vertices[b].position(time)) * 0.5f32;
+        let node = self.add_node(position, time);
+        let (prev, next) = (self.vertices[a].prev, self.vertices[b].next);
+        self.finish(a, node);
+        self.finish(b, node);
+        if prev == b {
+            return;
+        }
+        if prev == next {
+            // Triangle collapses into point.
+            self.finish(prev, node);
+            return;
+        }
+        let v = self.spawn(Vertex {
+            origin: position,
+            time,
+            velocity: Vec2::zero(),
+            prev,
+            next,
+            edge_in: self.vertices[a].edge_in,
+            edge_out: self.vertices[b].edge_out,
+            node,
+            alive: true,
+        });
+        self.vertices[prev].next = v;
+        self.vertices[next].prev = v;
+    }
+
+    fn split_event(&mut self, r: usize, u: usize, time: f32) {
+        let w = self.vertices[u].next;
+        let position = self.vertices[r].position(time);
+        let node = self.add_node(position, time);
+        let (prev, next) = (self.vertices[r].prev, self.vertices[r].next);
+        let edge = self.vertices[u].edge_out;
+        self.finish(r, node);
+        let template = Vertex {
+            origin: position,
+            time,
+            velocity: Vec2::zero(),
+            prev,
+            next: w,
+            edge_in: self.vertices[r].edge_in,
+            edge_out: edge,
+            node,
+            alive: true,
+        };
+        let left = self.spawn(template.clone());
+        let right = self.spawn(Vertex {
+            prev: u,
+            next,
+            edge_in: edge,
+            edge_out: self.vertices[r].edge_out,
+            ..template
+        });
+        self.vertices[prev].next = left;
+        self.vertices[w].prev = left;
+        self.vertices[u].next = right;
+        self.vertices[next].prev = right;
+        self.close_degenerate(left);
+        self.close_degenerate(right);
+    }
+
+    /// Finishes wavefront loop, that consists of less than three vertices.
+    fn close_degenerate(&mut self, v: usize) {
+        if !self.vertices[v].alive {
+            return;
+        }
+        let next = self.vertices[v].next;
+        if next == v {
+            self.vertices[v].alive = false;
+        } else if self.vertices[next].next == v {
+            let node = self.vertices[next].node;
+            self.finish(v, node);
+            self.vertices[next].alive = false;
+        }
+    }
+
+    /// Finishes wavefront loops without pending events, which can't shrink anymore: every
+    /// vertex is stationary or loop has zero area. Nodes of flat loop are connected in order
+    /// along it, nodes of stationary loop are connected around it.
+    fn close_stuck_loops(&mut self) {
+        let mut visited = vec![false; self.vertices.len()];
+        for start in 0..self.vertices.len() {
+            if !self.vertices[start].alive || visited[start] {
+                continue;
+            }
+            let mut ring = vec![start];
+            let mut v = self.vertices[start].next;
+            while v != start {
+                ring.push(v);
+                v = self.vertices[v].next;
+            }
+            for &v in &ring {
+                visited[v] = true;
+            }
+            let pending = ring.iter().any(|&a| self.edge_event_time(a).is_some())
+                || ring.iter().any(|&r| {
+                    self.is_reflex(r) && ring.iter().any(|&u| self.split_event_time(r, u).is_some())
+                });
+            if pending {
+                continue;
+            }
+            let origin = self.vertices[start].position(self.now);
+            let positions: Vec<Vec2> = ring
+                .iter()
+                .map(|&v| self.vertices[v].position(self.now) - origin)
+                .collect();
+            let len = positions.len();
+            let (mut area, mut perimeter) = (0f32, 0f32);
+            for i in 0..len {
+                let (p, q) = (positions[i], positions[(i + 1) % len]);
+                area += p.perp_dot(q);
+                perimeter += (q - p).length();
+            }
+            let flat = area.abs() <= self.epsilon * perimeter;
+            let stationary = ring
+                .iter()
+                .all(|&v| self.vertices[v].velocity == Vec2::zero());
+            if flat || stationary {
+                self.close_loop(&ring, flat);
+            }
+        }
+    }
+
+    fn close_loop(&mut self, ring: &[usize], flat: bool) {
+        let mut nodes = Vec::with_capacity(ring.len());
+        for &v in ring {
+            let position = self.vertices[v].position(self.now);
+            let node = self.vertices[v].node;
+            let node = if (self.nodes[node].position - position).length() <= self.epsilon {
+                node
+            } else {
+                self.add_node(position, self.now)
+            };
+            self.finish(v, node);
+            nodes.push(node);
+        }
+        let position = |node: usize| self.nodes[node].position;
+        let mut connected: Vec<(usize, usize)> = if flat {
+            // Order along direction from the first node to the farthest one.
+            let first = position(nodes[0]);
+            let far = nodes
+                .iter()
+                .map(|&n| position(n) - first)
+                .max_by(|a, b| {
+                    total_cmp(f64::from(a.length_squared()), f64::from(b.length_squared()))
+                })
+                .unwrap();
+            nodes.sort_by(|&a, &b| {
+                let (a, b) = (
+                    (position(a) - first).dot(far),
+                    (position(b) - first).dot(far),
+                );
+                total_cmp(f64::from(a), f64::from(b))
+            });
+            nodes.windows(2).map(|pair| (pair[0], pair[1])).collect()
+        } else {
+            (0..nodes.len())
+                .map(|i| (nodes[i], nodes[(i + 1) % nodes.len()]))
+                .collect()
+        };
+        connected.retain(|&(a, b)| a != b);
+        self.arcs.extend(connected);
+    }
+
+    fn run(mut self) -> StraightSkeleton {
+        // Every edge event removes vertex and every split event, at most one per reflex
+        // vertex, adds two, so there are less than four events per input vertex.
+        let limit = 4 * self.vertices.len();
+        let mut events = 0;
+        self.close_stuck_loops();
+        while let Some((time, event)) = self.next_event() {
+            events += 1;
+            debug_assert!(
+                events <= limit,
+                "wavefront must collapse in {} events",
+                limit
+            );
+            self.now = time.max(self.now);
+            match event {
+                Event::Edge(a) => self.edge_event(a, self.now),
+                Event::Split(r, u) => self.split_event(r, u, self.now),
+            }
+            self.close_stuck_loops();
+        }
+        self.merge_coincident_nodes()
+    }
+
+    /// Merges nodes at the same position, which appear at simultaneous events.
+    fn merge_coincident_nodes(self) -> StraightSkeleton {
+        let mut remap: Vec<usize> = (0..self.nodes.len()).collect();
+        let mut nodes: Vec<SkeletonNode> = Vec::with_capacity(self.nodes.len());
+        for (i, node) in self.nodes.iter().enumerate() {
+            let same = nodes.iter().position(|other| {
+                other.time > 0f32
+                    && node.time > 0f32
+                    && (other.position - node.position).length() <= self.epsilon * 10f32
+            });
+            remap[i] = match same {
+                Some(j) => j,
+                None => {
+                    nodes.push(*node);
+                    nodes.len() - 1
+                }
+            };
+        }
+        let mut arcs: Vec<(usize, usize)> = Vec::with_capacity(self.arcs.len());
+        for &(a, b) in &self.arcs {
+            let (a, b) = (remap[a], remap[b]);
+            let duplicate = arcs
+                .iter()
+                .any(|&(x, y)| (x == a && y == b) || (x == b && y == a));
+            if a != b && !duplicate {
+                arcs.push(if nodes[a].time <= nodes[b].time {
+                    (a, b)
+                } else {
+                    (b, a)
+                });
+            }
+        }
+        StraightSkeleton {
+            nodes,
+            arcs,
+            edges: self.source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::outline::Outline;
+    use crate::polygon::Polygon;
+    use glam::{Vec2, Vec3};
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Outline {
+        outline(&[(x, y), (x + w, y), (x + w, y + h), (x, y + h)])
+    }
+
+    /// Every vertex of roof face must lie on plane rising from its edge with `slope`.
+    fn assert_planar_faces(faces: &[Vec<Vec3>], slope: f32) {
+        for face in faces {
+            let (a, b) = (face[0].truncate(), face[1].truncate());
+            let dir = (b - a).normalize();
+            let normal = Vec2::new(-dir.y(), dir.x());
+            for p in face {
+                let distance = (p.truncate() - a).dot(normal);
+                assert!((distance * slope - p.z()).abs() < 1e-3f32, "{:?}", face);
+            }
+        }
+    }
+
+    fn projected_area(faces: &[Vec<Vec3>]) -> f32 {
+        let ring_area =
+            |face: &Vec<Vec3>| Outline::new(face.iter().map(|p| p.truncate())).signed_area();
+        faces.iter().map(ring_area).sum()
+    }
+
+    #[test]
+    fn rectangle() {
+        let skeleton = rect(0f32, 0f32, 4f32, 2f32).straight_skeleton();
+        assert_eq!(skeleton.nodes().len(), 6);
+        assert_eq!(skeleton.arcs().len(), 5);
+        let inner: Vec<_> = skeleton.nodes()[4..].iter().collect();
+        for node in inner {
+            assert!((node.time - 1f32).abs() < 1e-5f32);
+            assert!((node.position.y() - 1f32).abs() < 1e-5f32);
+        }
+
+        let faces = skeleton.roof_faces(0.5f32);
+        assert_eq!(faces.len(), 4);
+        assert_eq!(faces[0].len(), 4);
+        assert_eq!(faces[1].len(), 3);
+        assert_planar_faces(&faces, 0.5f32);
+        assert!((projected_area(&faces) - 8f32).abs() < 1e-4f32);
+
+        // Collinear vertex on bottom edge leaves flat wavefront, which must be closed.
+        let collinear = outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (4f32, 0f32),
+            (4f32, 2f32),
+            (0f32, 2f32),
+        ]);
+        let skeleton = collinear.straight_skeleton();
+        assert_eq!(skeleton.nodes().len(), 8);
+        assert_eq!(skeleton.arcs().len(), 7);
+        let position = |n: usize| skeleton.nodes()[n].position;
+        let connected = |a: Vec2, b: Vec2| {
+            skeleton.arcs().iter().any(|&(x, y)| {
+                let close = |p: Vec2, q: Vec2| (p - q).length() < 1e-5f32;
+                (close(position(x), a) && close(position(y), b))
+                    || (close(position(x), b) && close(position(y), a))
+            })
+        };
+        assert!(connected(Vec2::new(2f32, 0f32), Vec2::new(2f32, 1f32)));
+        assert!(connected(Vec2::new(1f32, 1f32), Vec2::new(2f32, 1f32)));
+        assert!(connected(Vec2::new(2f32, 1f32), Vec2::new(3f32, 1f32)));
+
+        let faces = skeleton.roof_faces(1f32);
+        let sizes: Vec<usize> = faces.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![4, 4, 3, 5, 3]);
+        assert_planar_faces(&faces, 1f32);
+        assert!((projected_area(&faces) - 8f32).abs() < 1e-4f32);
+    }
+
+    #[test]
+    fn square_has_single_peak() {
+        let skeleton = rect(0f32, 0f32, 2f32, 2f32).straight_skeleton();
+        assert_eq!(skeleton.nodes().len(), 5);
+        assert_eq!(skeleton.arcs().len(), 4);
+        let peak = skeleton.nodes()[4];
+        assert!((peak.position - Vec2::new(1f32, 1f32)).length() < 1e-5f32);
+        assert!((peak.time - 1f32).abs() < 1e-5f32);
+    }
+
+    #[test]
+    fn split_event() {
+        // Deep notch from the top: reflex vertices hit the bottom edge.
+        let notched = outline(&[
+            (0f32, 0f32),
+            (10f32, 0f32),
+            (10f32, 4f32),
+            (6f32, 4f32),
+            (5f32, 1f32),
+            (4f32, 4f32),
+            (0f32, 4f32),
+        ]);
+        let skeleton = notched.straight_skeleton();
+        let faces = skeleton.roof_faces(1f32);
+        assert_eq!(faces.len(), notched.len());
+        assert_planar_faces(&faces, 1f32);
+        assert!((projected_area(&faces) - notched.signed_area()).abs() < 1e-3f32);
+    }
+
+    #[test]
+    fn l_shape() {
+        let l_shape = outline(&[
+            (0f32, 0f32),
+            (4f32, 0f32),
+            (4f32, 2f32),
+            (2f32, 2f32),
+            (2f32, 4f32),
+            (0f32, 4f32),
+        ]);
+        let faces = l_shape.straight_skeleton().roof_faces(1f32);
+        assert_planar_faces(&faces, 1f32);
+        assert!((projected_area(&faces) - l_shape.signed_area()).abs() < 1e-3f32);
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let hole = Outline::new(
+            rect(4f32, 4f32, 2f32, 2f32)
+                .vertices()
+                .iter()
+                .rev()
+                .copied(),
+        );
+        let polygon = Polygon::new(rect(0f32, 0f32, 10f32, 10f32), vec![hole]).unwrap();
+        let skeleton = polygon.straight_skeleton();
+        for node in &skeleton.nodes()[8..] {
+            assert!((node.time - 2f32).abs() < 1e-4f32);
+        }
+        let faces = skeleton.roof_faces(1f32);
+        assert_eq!(faces.len(), 8);
+        assert_planar_faces(&faces, 1f32);
+        assert!((projected_area(&faces) - polygon.area()).abs() < 1e-3f32);
+    }
+}
@@ -0,0 +1,529 @@
+//! Bentley–Ottmann sweep for intersections of outline edges.
+//!
+//! Sweep line moves in lexicographic order of points. Crossings of edges are kept as exact
+//! homogeneous points, so events are processed in exact order and all edges passing through
+//! event point are found by exact predicates. Active edges are stored in [`BTreeSet`]: they
+//! are ordered by position at the later left endpoint, and pairs, which crossing sweep line has
+//! passed, in reverse. Takes `O((n + k) log n)` time for `n` edges and `k` intersections.
+use crate::geometry::Expansion;
+use crate::outline::Outline;
+use crate::point::{Point, Scalar};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::Bound;
+use std::rc::Rc;
+
+/// Edge `edge` of outline with index `outline`. Edge `i` goes from vertex `i` to vertex `i+1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeRef {
+    /// Index of outline in order of input.
+    pub outline: usize,
+    /// Index of edge in outline.
+    pub edge: usize,
+}
+
+/// Common point of two edges. Edges which overlap are reported once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection<P> {
+    /// Intersecting edges, the first one is less.
+    pub edges: (EdgeRef, EdgeRef),
+    /// Common point of edges. Crossings are rounded to the nearest point of type `P`.
+    pub point: P,
+}
+
+type Xy = [f64; 2];
+
+fn lex(p: Xy, q: Xy) -> Ordering {
+    p[0].partial_cmp(&q[0])
+        .unwrap_or(Ordering::Equal)
+        .then(p[1].partial_cmp(&q[1]).unwrap_or(Ordering::Equal))
+}
+
+/// Event point ordered lexicographically.
+#[derive(Debug, Clone)]
+enum Key {
+    Vertex(Xy),
+    /// Point `(x / w, y / w)` with positive `w`.
+    Crossing {
+        x: Expansion,
+        y: Expansion,
+        w: Expansion,
+    },
+}
+
+impl Key {
+    /// Crossing point of segments from `a` to `b` and from `c` to `d`, which aren't parallel.
+    fn crossing(a: Xy, b: Xy, c: Xy, d: Xy) -> Self {
+        let diff = |p: Xy, q: Xy, k: usize| Expansion::diff(p[k], q[k]);
+        let cross = |(a, b): (Xy, Xy), (c, d): (Xy, Xy)| {
+            diff(b, a, 0)
+                .mul(&diff(d, c, 1))
+                .sub(&diff(b, a, 1).mul(&diff(d, c, 0)))
+        };
+        // Crossing is `a + (b - a) * t / w`.
+        let w = cross((a, b), (c, d));
+        let t = cross((a, c), (c, d));
+        let coordinate = |k: usize| w.scale(a[k]).add(&diff(b, a, k).mul(&t));
+        let (x, y) = (coordinate(0), coordinate(1));
+        if w.sign() == Ordering::Less {
+            Key::Crossing {
+                x: x.neg(),
+                y: y.neg(),
+                w: w.neg(),
+            }
+        } else {
+            Key::Crossing { x, y, w }
+        }
+    }
+
+    fn homogeneous(&self) -> (Expansion, Expansion, Expansion) {
+        match self {
+            Key::Vertex(p) => (
+                Expansion::new(p[0]),
+                Expansion::new(p[1]),
+                Expansion::new(1f64),
+            ),
+            Key::Crossing { x, y, w } => (x.clone(), y.clone(), w.clone()),
+        }
+    }
+
+    /// Nearest point with `f64` coordinates.
+    fn approx(&self) -> Xy {
+        match self {
+            Key::Vertex(p) => *p,
+            Key::Crossing { x, y, w } => {
+                let w = w.estimate();
+                [x.estimate() / w + 0f64, y.estimate() / w + 0f64]
+            }
+        }
+    }
+
+    /// `Greater` if point lies at left side of directed line from `a` to `b`.
+    fn side(&self, a: Xy, b: Xy) -> Ordering {
+        match self {
+            Key::Vertex(p) => Point::orient(a, b, *p),
+            Key::Crossing { x, y, w } => {
+                let (dx, dy) = (Expansion::diff(b[0], a[0]), Expansion::diff(b[1], a[1]));
+                let (px, py) = (x.sub(&w.scale(a[0])), y.sub(&w.scale(a[1])));
+                dx.mul(&py).sub(&dy.mul(&px)).sign()
+            }
+        }
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let (Key::Vertex(p), Key::Vertex(q)) = (self, other) {
+            return lex(*p, *q);
+        }
+        let (x1, y1, w1) = self.homogeneous();
+        let (x2, y2, w2) = other.homogeneous();
+        let compare = |a: &Expansion, b: &Expansion| a.mul(&w2).sub(&b.mul(&w1)).sign();
+        compare(&x1, &x2).then_with(|| compare(&y1, &y2))
+    }
+}
+
+/// Test if segments from `a` to `b` and from `c` to `d` cross at single inner point of both.
+fn crosses((a, b): (Xy, Xy), (c, d): (Xy, Xy)) -> bool {
+    let opposite = |p: Ordering, q: Ordering| p != Ordering::Equal && p == q.reverse();
+    opposite(Point::orient(a, b, c), Point::orient(a, b, d))
+        && opposite(Point::orient(c, d, a), Point::orient(c, d, b))
+}
+
+/// Pairs of edges, which crossing sweep line has passed. Pairs are added only while both edges
+/// are out of status, so order of edges in status stays consistent.
+type Crossed = Rc<RefCell<HashSet<(usize, usize)>>>;
+
+/// Active edge from `left` to `right`, which is lexicographically greater.
+#[derive(Debug, Clone)]
+struct Piece {
+    left: Xy,
+    right: Xy,
+    edge: usize,
+    crossed: Crossed,
+    /// Point and order relative to pieces passing through it for degenerate pieces bounding
+    /// the range of such pieces.
+    probe: Option<(Rc<Key>, Ordering)>,
+}
+
+impl Piece {
+    fn probes(p: Key, crossed: &Crossed) -> (Piece, Piece) {
+        let p = Rc::new(p);
+        let probe = |order| Piece {
+            left: [0f64; 2],
+            right: [0f64; 2],
+            edge: 0,
+            crossed: crossed.clone(),
+            probe: Some((p.clone(), order)),
+        };
+        (probe(Ordering::Less), probe(Ordering::Greater))
+    }
+
+    fn segment(&self) -> (Xy, Xy) {
+        (self.left, self.right)
+    }
+}
+
+impl PartialEq for Piece {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Piece {}
+
+impl PartialOrd for Piece {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Piece {
+    /// Bottom to top order of pieces, which both span current sweep line.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.probe, &other.probe) {
+            (Some((_, a)), Some((_, b))) => return a.cmp(b),
+            (Some((p, order)), None) => {
+                return p.side(other.left, other.right).then(*order);
+            }
+            (None, Some(_)) => return other.cmp(self).reverse(),
+            (None, None) => {}
+        }
+        if self.edge == other.edge {
+            return Ordering::Equal;
+        }
+        if lex(self.left, other.left) == Ordering::Greater {
+            return other.cmp(self).reverse();
+        }
+        let (a, b) = (self.left, self.right);
+        let side = match Point::orient(a, b, other.left) {
+            Ordering::Equal => Point::orient(a, b, other.right),
+            side => side,
+        };
+        // `other` at left side of `self` is above it. Overlapping pieces are ordered by edge.
+        let order = side.reverse().then(self.edge.cmp(&other.edge));
+        let pair = (self.edge.min(other.edge), self.edge.max(other.edge));
+        if self.crossed.borrow().contains(&pair) {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+struct Sweep<F> {
+    /// Endpoints of edges, `left` first.
+    edges: Vec<(Xy, Xy)>,
+    /// Test if pair of edges is not reported.
+    skip: F,
+    /// Event points with edges starting at them.
+    events: BTreeMap<Key, Vec<usize>>,
+    status: BTreeSet<Piece>,
+    crossed: Crossed,
+    found: HashSet<(usize, usize)>,
+    result: Vec<(usize, usize, Xy)>,
+}
+
+impl<F: Fn(usize, usize) -> bool> Sweep<F> {
+    fn insert(&mut self, edge: usize) -> Piece {
+        let (left, right) = self.edges[edge];
+        let piece = Piece {
+            left,
+            right,
+            edge,
+            crossed: self.crossed.clone(),
+            probe: None,
+        };
+        self.status.insert(piece.clone());
+        piece
+    }
+
+    /// Reports edges `a` and `b` meeting at `p`, unless they are skipped or already reported.
+    fn report(&mut self, a: usize, b: usize, p: Xy) {
+        let (a, b) = (a.min(b), a.max(b));
+        if !(self.skip)(a, b) && self.found.insert((a, b)) {
+            self.result.push((a, b, p));
+        }
+    }
+
+    /// Schedules crossing of neighbor pieces, if it is ahead of sweep line.
+    fn check(&mut self, s: Option<Piece>, t: Option<Piece>, p: &Key) {
+        if let (Some(s), Some(t)) = (s, t) {
+            if crosses(s.segment(), t.segment()) {
+                let q = Key::crossing(s.left, s.right, t.left, t.right);
+                if q > *p {
+                    self.events.entry(q).or_default();
+                }
+            }
+        }
+    }
+
+    fn process(&mut self, p: Key, starts: Vec<usize>) {
+        let (low, high) = Piece::probes(p.clone(), &self.crossed);
+        let through: Vec<Piece> = self.status.range(&low..=&high).cloned().collect();
+        for piece in &through {
+            self.status.remove(piece);
+        }
+        let edges: Vec<usize> = through
+            .iter()
+            .map(|piece| piece.edge)
+            .chain(starts)
+            .collect();
+        let approx = p.approx();
+        for (i, &a) in edges.iter().enumerate() {
+            for &b in &edges[i + 1..] {
+                self.report(a, b, approx);
+                if crosses(self.edges[a], self.edges[b]) {
+                    self.crossed.borrow_mut().insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+
+        let mut pieces = Vec::new();
+        for edge in edges {
+            if Key::Vertex(self.edges[edge].1) > p {
+                pieces.push(self.insert(edge));
+            }
+        }
+        match (pieces.iter().min().cloned(), pieces.iter().max().cloned()) {
+            (Some(lowest), Some(highest)) => {
+                let below = self.status.range(..&lowest).next_back().cloned();
+                self.check(below, Some(lowest), &p);
+                let above = self
+                    .status
+                    .range((Bound::Excluded(&highest), Bound::Unbounded))
+                    .next()
+                    .cloned();
+                self.check(Some(highest), above, &p);
+            }
+            _ => {
+                let below = self.status.range(..&low).next_back().cloned();
+                let above = self.status.range(&high..).next().cloned();
+                self.check(below, above, &p);
+            }
+        }
+    }
+}
+
+fn to_xy<P: Point>(p: P) -> Xy {
+    [p.x().to_f64() + 0f64, p.y().to_f64() + 0f64]
+}
+
+/// Pairs of intersecting `edges`, except for `skip`ped ones, with their common points.
+/// Edges with non-finite coordinates **MUST** be filtered out.
+fn sweep(edges: Vec<(Xy, Xy)>, skip: impl Fn(usize, usize) -> bool) -> Vec<(usize, usize, Xy)> {
+    let mut events: BTreeMap<Key, Vec<usize>> = BTreeMap::new();
+    let edges: Vec<(Xy, Xy)> = edges
+        .into_iter()
+        .map(|(from, to)| {
+            if lex(from, to) == Ordering::Greater {
+                (to, from)
+            } else {
+                (from, to)
+            }
+        })
+        .collect();
+    for (i, &(left, right)) in edges.iter().enumerate() {
+        events.entry(Key::Vertex(left)).or_default().push(i);
+        events.entry(Key::Vertex(right)).or_default();
+    }
+
+    let mut sweep = Sweep {
+        edges,
+        skip,
+        events,
+        status: BTreeSet::new(),
+        crossed: Rc::new(RefCell::new(HashSet::new())),
+        found: HashSet::new(),
+        result: Vec::new(),
+    };
+    while let Some((p, starts)) = pop_first(&mut sweep.events) {
+        sweep.process(p, starts);
+    }
+    sweep.result
+}
+
+/// Finds all pairs of intersecting edges of `outlines`, including edges of the same outline.
+/// Neighbor edges of the same outline share vertex, so they are never reported, even if they
+/// overlap. Edges with non-finite coordinates are ignored.
+///
+/// Predicates are exact for coordinates representable in `f64`.
+pub fn intersections<'a, P: Point + 'a>(
+    outlines: impl IntoIterator<Item = &'a Outline<P>>,
+) -> Vec<Intersection<P>> {
+    let mut refs = Vec::new();
+    let mut sizes = Vec::new();
+    let mut edges = Vec::new();
+    for (i, outline) in outlines.into_iter().enumerate() {
+        sizes.push(outline.len());
+        for (j, (from, to)) in outline.edges().enumerate() {
+            let (from, to) = (to_xy(from), to_xy(to));
+            if from.iter().chain(to.iter()).all(|x| x.is_finite()) {
+                refs.push(EdgeRef {
+                    outline: i,
+                    edge: j,
+                });
+                edges.push((from, to));
+            }
+        }
+    }
+
+    let neighbors = |a: usize, b: usize| {
+        let (ra, rb): (EdgeRef, EdgeRef) = (refs[a], refs[b]);
+        let n = sizes[ra.outline];
+        ra.outline == rb.outline && ((ra.edge + 1) % n == rb.edge || (rb.edge + 1) % n == ra.edge)
+    };
+    let from_xy = |p: Xy| P::from_xy(P::Scalar::from_f64(p[0]), P::Scalar::from_f64(p[1]));
+    sweep(edges, neighbors)
+        .into_iter()
+        .map(|(a, b, p)| Intersection {
+            edges: (refs[a], refs[b]),
+            point: from_xy(p),
+        })
+        .collect()
+}
+
+/// Pairs `(i, j)` of indices of `segments` with common points, where `i < j`. Unlike
+/// [`intersections`], segments sharing endpoint are reported too. Segments with non-finite
+/// coordinates are ignored.
+pub(crate) fn segment_intersections<P: Point>(segments: &[(P, P)]) -> Vec<(usize, usize)> {
+    let mut indices = Vec::with_capacity(segments.len());
+    let mut edges = Vec::with_capacity(segments.len());
+    for (i, &(from, to)) in segments.iter().enumerate() {
+        let (from, to) = (to_xy(from), to_xy(to));
+        if from.iter().chain(to.iter()).all(|x| x.is_finite()) {
+            indices.push(i);
+            edges.push((from, to));
+        }
+    }
+    sweep(edges, |_, _| false)
+        .into_iter()
+        .map(|(a, b, _)| (indices[a], indices[b]))
+        .collect()
+}
+
+fn pop_first<K: Ord + Clone, V>(map: &mut BTreeMap<K, V>) -> Option<(K, V)> {
+    let key = map.keys().next()?.clone();
+    map.remove(&key).map(|value| (key, value))
+}
+
+impl<P: Point> Outline<P> {
+    /// Pairs of edges, which intersect each other, except for neighbors. See [`intersections`].
+    pub fn self_intersections(&self) -> Vec<Intersection<P>> {
+        intersections(std::iter::once(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{intersections, EdgeRef};
+    use crate::outline::Outline;
+    use crate::testing::Random;
+    use crate::validation::intersection;
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    fn edge(outline: usize, edge: usize) -> EdgeRef {
+        EdgeRef { outline, edge }
+    }
+
+    /// Pairs of intersecting non-neighbor edges found by checking every pair.
+    fn brute_force(outlines: &[Outline]) -> Vec<(EdgeRef, EdgeRef)> {
+        let mut all = Vec::new();
+        for (i, outline) in outlines.iter().enumerate() {
+            let n = outline.len();
+            all.extend(outline.edges().enumerate().map(|(j, e)| (edge(i, j), e, n)));
+        }
+        let mut pairs = Vec::new();
+        for (k, &(a, (p, q), n)) in all.iter().enumerate() {
+            for &(b, (r, s), _) in &all[k + 1..] {
+                let neighbors = a.outline == b.outline
+                    && ((a.edge + 1) % n == b.edge || (b.edge + 1) % n == a.edge);
+                if !neighbors && intersection(p, q, r, s).is_some() {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn figure_eight() {
+        let figure_eight = outline(&[(0f32, 0f32), (2f32, 2f32), (2f32, 0f32), (0f32, 2f32)]);
+        let found = figure_eight.self_intersections();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].edges, (edge(0, 0), edge(0, 2)));
+        assert_eq!(found[0].point, Vec2::new(1f32, 1f32));
+
+        let square = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        assert!(square.self_intersections().is_empty());
+    }
+
+    #[test]
+    fn several_outlines() {
+        let a = outline(&[(0f32, 0f32), (2f32, 0f32), (2f32, 2f32), (0f32, 2f32)]);
+        // Shares vertex and vertical edge with `a` and crosses its top edge.
+        let b = outline(&[
+            (2f32, 0f32),
+            (4f32, 0f32),
+            (4f32, 1f32),
+            (2f32, 1f32),
+            (1f32, 3f32),
+        ]);
+        let mut found: Vec<_> = intersections(vec![&a, &b])
+            .into_iter()
+            .map(|i| i.edges)
+            .collect();
+        found.sort();
+        assert_eq!(found, brute_force(&[a, b]));
+        assert_eq!(found.len(), 8);
+    }
+
+    #[test]
+    fn random_against_brute_force() {
+        // Small grid gives many collinear, overlapping and touching edges, scaled grid gives
+        // crossings close to each other.
+        let mut random = Random(0x5eed);
+        for round in 0..400 {
+            let scale = if round % 2 == 0 {
+                (1f32, 1f32)
+            } else {
+                (0.37f32, 0.61f32)
+            };
+            let count = 1 + random.next(3) as usize;
+            let outlines: Vec<Outline> = (0..count)
+                .map(|_| {
+                    let len = 3 + random.next(7) as usize;
+                    let points: Vec<_> = (0..len)
+                        .map(|_| (random.next(6) * scale.0, random.next(6) * scale.1))
+                        .collect();
+                    outline(&points)
+                })
+                .collect();
+            let mut found: Vec<_> = intersections(&outlines)
+                .into_iter()
+                .map(|i| i.edges)
+                .collect();
+            found.sort();
+            assert_eq!(found, brute_force(&outlines), "round {}", round);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Helpers shared by tests.
+
+/// Linear congruential generator, so tests are reproducible without dependencies.
+pub(crate) struct Random(pub(crate) u64);
+
+impl Random {
+    fn next_u32(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        (self.0 >> 33) as u32
+    }
+
+    /// Integer number in range `[0, max]` as `f32`.
+    pub(crate) fn next(&mut self, max: u32) -> f32 {
+        (u64::from(self.next_u32()) % u64::from(max + 1)) as f32
+    }
+
+    /// Integer number in range `[min, max]`.
+    pub(crate) fn range(&mut self, min: i32, max: i32) -> i32 {
+        min + (self.next_u32() % (max - min + 1) as u32) as i32
+    }
+}
@@ -0,0 +1,512 @@
+use crate::geometry::{in_triangle, orient};
+use crate::outline::Outline;
+use crate::point::Point;
+use crate::polygon::Polygon;
+use glam::Vec2;
+use std::cmp::Ordering;
+
+impl<P: Point> Outline<P> {
+    /// Triangulates outline with ear clipping.
+    /// Returns triples of vertex indices. Every triangle is counter-clockwise.
+    /// Vertices lying on straight edges are kept, while coincident and spike vertices don't
+    /// produce degenerate triangles and may be absent in result.
+    /// Outline **MUST** be simple and counter-clockwise (see [`Outline::validate`]). Otherwise
+    /// clipping stops, when no ear is left, and triangles cover only part of outline.
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        let ring: Vec<usize> = (0..self.len()).collect();
+        ear_clip(self.vertices(), &ring)
+    }
+}
+
+impl Polygon {
+    /// Triangulates polygon with holes. Every hole is merged into outer outline with pair of
+    /// bridge edges, then merged ring is triangulated with ear clipping.
+    /// Returns triples of indices into vertex buffer produced by [`Polygon::vertices`]. Every
+    /// triangle is counter-clockwise. Holes, that are not inside outer outline, are ignored.
+    /// Like [`Outline::triangulate`], gives partial result for intersecting outlines.
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        let points: Vec<Vec2> = self.vertices().collect();
+        let ring = merge_holes(self, &points);
+        ear_clip(&points, &ring)
+    }
+}
+
+/// Builds single ring of indices into `points` by bridging holes to outer outline.
+/// Holes are processed from left to right, so bridge may end at previously merged hole.
+fn merge_holes(polygon: &Polygon, points: &[Vec2]) -> Vec<usize> {
+    let mut ring: Vec<usize> = (0..polygon.outline().len()).collect();
+    let mut holes = Vec::with_capacity(polygon.holes().len());
+    let mut start = ring.len();
+    for hole in polygon.holes() {
+        let len = hole.len();
+        if len >= 3 {
+            let leftmost = (start..start + len)
+                .min_by(|&a, &b| cmp_xy(points[a], points[b]))
+                .unwrap();
+            holes.push((start, len, leftmost));
+        }
+        start += len;
+    }
+    holes.sort_by(|a, b| cmp_xy(points[a.2], points[b.2]));
+
+    for (start, len, leftmost) in holes {
+        if let Some(pos) = find_bridge(points, &ring, points[leftmost]) {
+            let hole_ring = (0..=len).map(|k| start + (leftmost - start + k) % len);
+            let mut merged = Vec::with_capacity(ring.len() + len + 2);
+            merged.extend_from_slice(&ring[..=pos]);
+            merged.extend(hole_ring);
+            merged.push(ring[pos]);
+            merged.extend_from_slice(&ring[pos + 1..]);
+            ring = merged;
+        }
+    }
+    ring
+}
+
+/// Finds position of ring vertex, which can be connected with hole vertex `h` by bridge.
+/// Ray is casted from `h` to the left, and the nearest visible vertex near hit point is chosen.
+fn find_bridge(points: &[Vec2], ring: &[usize], h: Vec2) -> Option<usize> {
+    let n = ring.len();
+    let point = |pos: usize| points[ring[pos % n]];
+
+    let mut qx = f32::NEG_INFINITY;
+    let mut m = None;
+    for i in 0..n {
+        let (a, b) = (point(i), point(i + 1));
+        // Edges at the left of hole go downward, because inner area is at their left side.
+        if a.y() >= h.y() && h.y() >= b.y() && a.y() != b.y() {
+            let x = a.x() + (h.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+            if x <= h.x() && x > qx {
+                qx = x;
+                if x == h.x() {
+                    if h.y() == a.y() {
+                        return Some(i);
+                    }
+                    if h.y() == b.y() {
+                        return Some((i + 1) % n);
+                    }
+                }
+                m = Some(if a.x() < b.x() { i } else { (i + 1) % n });
+            }
+        }
+    }
+    let mut m = m?;
+    if qx == h.x() {
+        return Some(m);
+    }
+
+    // Vertices inside triangle (h, q, m) may hide `m` from `h`. Choose one of them, which has
+    // minimal angle with the ray.
+    let q = Vec2::new(qx, h.y());
+    let mp = point(m);
+    let mut min_tan = f32::INFINITY;
+    for i in 0..n {
+        let p = point(i);
+        if h.x() >= p.x() && p.x() >= mp.x() && h.x() != p.x() && in_any_triangle(h, q, mp, p) {
+            let tan = (h.y() - p.y()).abs() / (h.x() - p.x());
+            let closer = tan < min_tan || (tan == min_tan && p.x() > point(m).x());
+            if closer && locally_inside(points, ring, i, h) {
+                m = i;
+                min_tan = tan;
+            }
+        }
+    }
+    Some(m)
+}
+
+/// Test if direction from ring vertex at `pos` to `target` goes inside of polygon.
+fn locally_inside(points: &[Vec2], ring: &[usize], pos: usize, target: Vec2) -> bool {
+    let n = ring.len();
+    let prev = points[ring[(pos + n - 1) % n]];
+    let that = points[ring[pos]];
+    let next = points[ring[(pos + 1) % n]];
+    if orient(prev, that, next) >= 0f64 {
+        orient(that, next, target) >= 0f64 && orient(that, target, prev) >= 0f64
+    } else {
+        orient(that, prev, target) <= 0f64 || orient(that, target, next) <= 0f64
+    }
+}
+
+fn in_any_triangle(a: Vec2, b: Vec2, c: Vec2, p: Vec2) -> bool {
+    if orient(a, b, c) >= 0f64 {
+        in_triangle(a, b, c, p)
+    } else {
+        in_triangle(a, c, b, p)
+    }
+}
+
+fn cmp_xy(a: Vec2, b: Vec2) -> Ordering {
+    a.x()
+        .partial_cmp(&b.x())
+        .unwrap_or(Ordering::Equal)
+        .then(a.y().partial_cmp(&b.y()).unwrap_or(Ordering::Equal))
+}
+
+/// Triangulates closed ring with ear clipping. Stops, when no ear is left.
+/// # Arguments
+/// * `points` - vertex buffer;
+/// * `ring` - indices of `points` in counter-clockwise order. Same index may occur several
+///   times (e.g. at ends of bridge edges);
+pub(crate) fn ear_clip<P: Point>(points: &[P], ring: &[usize]) -> Vec<[usize; 3]> {
+    if ring.len() < 3 {
+        return Vec::new();
+    }
+    EarClipper::new(points, ring).run()
+}
+
+/// Doubly linked list of ring nodes with cached set of reflex nodes.
+struct EarClipper<'a, P> {
+    points: &'a [P],
+    ring: &'a [usize],
+    prev: Vec<usize>,
+    next: Vec<usize>,
+    removed: Vec<bool>,
+    reflex: Vec<usize>,
+    len: usize,
+}
+
+impl<'a, P: Point> EarClipper<'a, P> {
+    fn new(points: &'a [P], ring: &'a [usize]) -> Self {
+        let len = ring.len();
+        let prev = (0..len).map(|i| (i + len - 1) % len).collect();
+        let next = (0..len).map(|i| (i + 1) % len).collect();
+        let mut clipper = EarClipper {
+            points,
+            ring,
+            prev,
+            next,
+            removed: vec![false; len],
+            reflex: Vec::new(),
+            len,
+        };
+        clipper.reflex = (0..len)
+            .filter(|&i| clipper.area(i) != Ordering::Greater)
+            .collect();
+        clipper
+    }
+
+    fn point(&self, node: usize) -> P {
+        self.points[self.ring[node]]
+    }
+
+    /// Orientation of triangle formed by node and its neighbors.
+    fn area(&self, node: usize) -> Ordering {
+        let (p, n) = (self.prev[node], self.next[node]);
+        P::orient(self.point(p), self.point(node), self.point(n))
+    }
+
+    fn unlink(&mut self, node: usize) {
+        let (p, n) = (self.prev[node], self.next[node]);
+        self.next[p] = n;
+        self.prev[n] = p;
+        self.removed[node] = true;
+        self.len -= 1;
+    }
+
+    fn clip(&mut self, node: usize, triangles: &mut Vec<[usize; 3]>) {
+        let (p, n) = (self.prev[node], self.next[node]);
+        triangles.push([self.ring[p], self.ring[node], self.ring[n]]);
+        self.unlink(node);
+        self.refresh_reflex();
+    }
+
+    fn refresh_reflex(&mut self) {
+        let mut reflex = std::mem::take(&mut self.reflex);
+        reflex.retain(|&r| !self.removed[r] && self.area(r) != Ordering::Greater);
+        self.reflex = reflex;
+    }
+
+    /// Test if no reflex vertex lies inside of triangle formed by node and its neighbors.
+    fn is_ear(&self, node: usize) -> bool {
+        let (p, n) = (self.prev[node], self.next[node]);
+        let (a, b, c) = (self.point(p), self.point(node), self.point(n));
+        self.reflex.iter().all(|&r| {
+            if r == p || r == node || r == n {
+                return true;
+            }
+            let q = self.point(r);
+            q == a || q == b || q == c || !in_triangle(a, b, c, q)
+        })
+    }
+
+    fn run(mut self) -> Vec<[usize; 3]> {
+        let mut triangles = Vec::with_capacity(self.len - 2);
+        let mut node = 0;
+        let mut fails = 0;
+        while self.len > 3 {
+            let area = self.area(node);
+            if area == Ordering::Equal && !self.is_straight(node) {
+                // Coincident or spike vertex doesn't bound any area.
+                let p = self.prev[node];
+                self.unlink(node);
+                self.refresh_reflex();
+                node = p;
+                fails = 0;
+            } else if area == Ordering::Greater && self.is_ear(node) {
+                let n = self.next[node];
+                self.clip(node, &mut triangles);
+                node = n;
+                fails = 0;
+            } else if fails >= self.len {
+                // Invalid input (e.g. self-intersecting) has no ear left.
+                return triangles;
+            } else {
+                node = self.next[node];
+                fails += 1;
+            }
+        }
+        if self.area(node) == Ordering::Greater {
+            let (p, n) = (self.prev[node], self.next[node]);
+            triangles.push([self.ring[p], self.ring[node], self.ring[n]]);
+        }
+        triangles
+    }
+
+    /// Test if node lies strictly between its neighbors on straight line.
+    fn is_straight(&self, node: usize) -> bool {
+        let (p, n) = (self.prev[node], self.next[node]);
+        let (prev, that, next) = (self.point(p), self.point(node), self.point(n));
+        let between = |a, b, c| (a < b && b < c) || (a > b && b > c);
+        between(prev.x(), that.x(), next.x()) || between(prev.y(), that.y(), next.y())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::orient;
+    use crate::outline::Outline;
+    use crate::point::Point;
+    use crate::polygon::Polygon;
+    use glam::Vec2;
+    use std::cmp::Ordering;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    fn triangles_area(outline: &Outline, triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|t| {
+                orient(
+                    outline[t[0] as isize],
+                    outline[t[1] as isize],
+                    outline[t[2] as isize],
+                ) as f32
+            })
+            .sum::<f32>()
+            * 0.5f32
+    }
+
+    fn assert_valid(outline: &Outline, triangles: &[[usize; 3]]) {
+        for t in triangles {
+            let area = orient(
+                outline[t[0] as isize],
+                outline[t[1] as isize],
+                outline[t[2] as isize],
+            );
+            assert!(area > 0f64);
+        }
+        let expected = outline.signed_area();
+        let actual = triangles_area(outline, triangles);
+        assert!((expected - actual).abs() <= expected * 1e-4f32);
+    }
+
+    #[test]
+    fn square() {
+        let outline = outline(&[(0f32, 0f32), (1f32, 0f32), (1f32, 1f32), (0f32, 1f32)]);
+        let triangles = outline.triangulate();
+        assert_eq!(triangles.len(), 2);
+        assert_valid(&outline, &triangles);
+    }
+
+    #[test]
+    fn concave() {
+        let outline = outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 1f32),
+            (1f32, 1f32),
+            (1f32, 2f32),
+            (0f32, 2f32),
+        ]);
+        let triangles = outline.triangulate();
+        assert_eq!(triangles.len(), 4);
+        assert_valid(&outline, &triangles);
+    }
+
+    #[test]
+    fn collinear_and_duplicates() {
+        let outline = outline(&[
+            (0f32, 0f32),
+            (1f32, 0f32),
+            (1f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 2f32),
+            (1f32, 2f32),
+            (0f32, 2f32),
+            (0f32, 1f32),
+        ]);
+        let triangles = outline.triangulate();
+        assert_valid(&outline, &triangles);
+    }
+
+    #[test]
+    fn degenerate() {
+        assert!(outline(&[]).triangulate().is_empty());
+        assert!(outline(&[(0f32, 0f32), (1f32, 0f32)])
+            .triangulate()
+            .is_empty());
+        let line = outline(&[(0f32, 0f32), (1f32, 0f32), (2f32, 0f32)]);
+        assert!(line.triangulate().is_empty());
+    }
+
+    #[test]
+    fn large_comb() {
+        // Comb with thousands of teeth has thousands of reflex vertices.
+        let teeth = 1000;
+        let mut points = vec![(teeth as f32 * 2f32, -1f32)];
+        for i in (0..teeth).rev() {
+            let x = i as f32 * 2f32;
+            points.push((x + 1.5f32, 10f32));
+            points.push((x + 1f32, 10f32));
+            points.push((x + 1f32, 0f32));
+            points.push((x, 0f32));
+        }
+        points.push((0f32, -1f32));
+        let outline = outline(&points);
+        let triangles = outline.triangulate();
+        assert_eq!(triangles.len(), outline.len() - 2);
+        assert_valid(&outline, &triangles);
+    }
+
+    #[test]
+    fn near_collinear_sliver() {
+        // Upper chain is above lower one by single ulp, so every turn is nearly straight.
+        let xs = [0.5f32, 1f32, 2f32, 3f32, 5f32, 8f32, 13f32, 21f32];
+        let next_up = |x: f32| f32::from_bits(x.to_bits() + 1);
+        let mut points: Vec<(f32, f32)> = xs.iter().map(|&x| (x, x)).collect();
+        points.extend(xs.iter().rev().map(|&x| (x, next_up(x))));
+        let outline = outline(&points);
+        let triangles = outline.triangulate();
+        assert_eq!(triangles.len(), outline.len() - 2);
+        for t in &triangles {
+            let [a, b, c] = t.map(|i| outline[i as isize]);
+            assert!(orient(a, b, c) > 0f64);
+        }
+    }
+
+    #[test]
+    fn self_intersecting() {
+        // Clockwise lobe has no ears, so only part of outline is covered.
+        let figure_eight = outline(&[(0f32, 0f32), (0f32, 4f32), (4f32, 0f32), (4f32, 2f32)]);
+        let triangles = figure_eight.triangulate();
+        assert!(triangles.len() < figure_eight.len() - 2);
+        // Triangles don't overlap.
+        for i in 0..40 {
+            for j in 0..40 {
+                let p = Vec2::new(i as f32 * 0.1f32 + 0.03f32, j as f32 * 0.1f32 + 0.07f32);
+                let covering = triangles.iter().filter(|t| {
+                    let [a, b, c] = t.map(|i| figure_eight[i as isize]);
+                    orient(a, b, p) > 0f64 && orient(b, c, p) > 0f64 && orient(c, a, p) > 0f64
+                });
+                assert!(covering.count() <= 1);
+            }
+        }
+    }
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Vec<(f32, f32)> {
+        vec![(x, y), (x + w, y), (x + w, y + h), (x, y + h)]
+    }
+
+    fn hole(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().rev().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    fn assert_valid_polygon(polygon: &Polygon, triangles: &[[usize; 3]]) {
+        let points: Vec<Vec2> = polygon.vertices().collect();
+        let mut area = 0f32;
+        for t in triangles {
+            let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+            let doubled = orient(a, b, c);
+            assert!(doubled > 0f64);
+            area += doubled as f32 * 0.5f32;
+            assert!(polygon.contains((a + b + c) / 3f32));
+        }
+        let expected = polygon.area();
+        assert!((expected - area).abs() <= expected * 1e-4f32);
+    }
+
+    #[test]
+    fn polygon_with_holes() {
+        let polygon = Polygon::new(
+            outline(&rect(0f32, 0f32, 10f32, 10f32)),
+            vec![
+                hole(&rect(1f32, 1f32, 2f32, 2f32)),
+                hole(&rect(5f32, 5f32, 3f32, 1f32)),
+                hole(&rect(1f32, 6f32, 1f32, 3f32)),
+            ],
+        )
+        .unwrap();
+        let triangles = polygon.triangulate();
+        assert_eq!(triangles.len(), polygon.vertices().count() + 2 * 3 - 2);
+        assert_valid_polygon(&polygon, &triangles);
+    }
+
+    #[test]
+    fn nested_holes() {
+        // Small hole lies in the bay of C-shaped hole, so its bridge can't go straight left.
+        let c_shape = [
+            (2f32, 2f32),
+            (8f32, 2f32),
+            (8f32, 3f32),
+            (3f32, 3f32),
+            (3f32, 7f32),
+            (8f32, 7f32),
+            (8f32, 8f32),
+            (2f32, 8f32),
+        ];
+        let polygon = Polygon::new(
+            outline(&rect(0f32, 0f32, 10f32, 10f32)),
+            vec![hole(&c_shape), hole(&rect(4f32, 4f32, 2f32, 2f32))],
+        )
+        .unwrap();
+        let triangles = polygon.triangulate();
+        assert_valid_polygon(&polygon, &triangles);
+    }
+
+    #[test]
+    fn touching_holes() {
+        let polygon = Polygon::new(
+            outline(&rect(0f32, 0f32, 10f32, 10f32)),
+            vec![
+                hole(&rect(2f32, 2f32, 2f32, 2f32)),
+                hole(&rect(4f32, 4f32, 2f32, 2f32)),
+                hole(&[(0f32, 7f32), (2f32, 6f32), (2f32, 8f32)]),
+            ],
+        )
+        .unwrap();
+        let triangles = polygon.triangulate();
+        assert_valid_polygon(&polygon, &triangles);
+    }
+
+    #[test]
+    fn integer_points() {
+        // Offset beyond `f32` precision doesn't affect integer outlines.
+        let base = 1i64 << 40;
+        let outline = Outline::new(
+            [(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2)]
+                .iter()
+                .map(|&(x, y)| [base + x, base + y]),
+        );
+        let triangles = outline.triangulate();
+        assert_eq!(triangles.len(), 5);
+        let mut area = 0i128;
+        for t in &triangles {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| outline[i as isize]);
+            assert_eq!(Point::orient(a, b, c), Ordering::Greater);
+            area += Outline::new([a, b, c].iter().copied()).doubled_signed_area();
+        }
+        assert_eq!(area, outline.doubled_signed_area());
+    }
+}
@@ -0,0 +1,369 @@
+use crate::outline::{Orientation, Outline};
+use crate::point::{Point, Scalar};
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+/// Problem found in outline by [`Outline::validate`]. Edge `i` goes from vertex `i` to vertex
+/// `i+1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlineIssue<P = Vec2> {
+    /// Outline has specified number of vertices, which is less than three.
+    TooFewVertices(usize),
+    /// Vertex with specified index has infinite or NaN coordinate.
+    NonFinite(usize),
+    /// Vertices with specified indices are equal, but not neighbors.
+    DuplicateVertex(usize, usize),
+    /// Edge with specified index has zero length.
+    ZeroLengthEdge(usize),
+    /// Outline turns back at vertex with specified index, so its edges overlap.
+    Spike(usize),
+    /// Edges which aren't neighbors intersect or touch at `point`. For overlapping edges
+    /// `point` is one of the common points.
+    SelfIntersection {
+        /// Indices of intersecting edges.
+        edges: (usize, usize),
+        /// Common point of edges. Rounded for integer points.
+        point: P,
+    },
+    /// Outline isn't counter-clockwise, as [`Outline::new`] requires.
+    WrongOrientation(Orientation),
+}
+
+impl<P: fmt::Debug> fmt::Display for OutlineIssue<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutlineIssue::TooFewVertices(n) => write!(f, "outline has only {} vertices", n),
+            OutlineIssue::NonFinite(i) => write!(f, "vertex {} is not finite", i),
+            OutlineIssue::DuplicateVertex(i, j) => {
+                write!(f, "vertices {} and {} are equal", i, j)
+            }
+            OutlineIssue::ZeroLengthEdge(i) => write!(f, "edge {} has zero length", i),
+            OutlineIssue::Spike(i) => write!(f, "outline turns back at vertex {}", i),
+            OutlineIssue::SelfIntersection { edges, point } => write!(
+                f,
+                "edges {} and {} intersect at {:?}",
+                edges.0, edges.1, point
+            ),
+            OutlineIssue::WrongOrientation(orientation) => {
+                write!(f, "outline is {:?}", orientation)
+            }
+        }
+    }
+}
+
+/// All problems of outline found by [`Outline::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport<P = Vec2> {
+    issues: Vec<OutlineIssue<P>>,
+}
+
+impl<P> ValidationReport<P> {
+    /// Test if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Found problems. Issues of the same kind follow in order of indices.
+    pub fn issues(&self) -> &[OutlineIssue<P>] {
+        &self.issues
+    }
+
+    /// Takes found problems.
+    pub fn into_issues(self) -> Vec<OutlineIssue<P>> {
+        self.issues
+    }
+}
+
+/// Reason of [`Outline::try_new`] failure
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineError<P = Vec2> {
+    report: ValidationReport<P>,
+}
+
+impl<P> OutlineError<P> {
+    /// Every problem of rejected vertices.
+    pub fn report(&self) -> &ValidationReport<P> {
+        &self.report
+    }
+}
+
+impl<P: fmt::Debug> fmt::Display for OutlineError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid outline")?;
+        for (i, issue) in self.report.issues.iter().enumerate() {
+            write!(f, "{} {}", if i == 0 { ":" } else { ";" }, issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: fmt::Debug> Error for OutlineError<P> {}
+
+/// Test if coordinates of `p` go in the same direction from `that` as coordinates of `q`.
+fn same_direction<P: Point>(that: P, p: P, q: P) -> bool {
+    let cmp = |a: P::Scalar, b: P::Scalar| a.partial_cmp(&b);
+    cmp(p.x(), that.x()) == cmp(q.x(), that.x()) && cmp(p.y(), that.y()) == cmp(q.y(), that.y())
+}
+
+/// Test if `p`, collinear with segment (`a`, `b`), lies on it.
+fn on_segment<P: Point>(a: P, b: P, p: P) -> bool {
+    let between =
+        |a: P::Scalar, b: P::Scalar, p: P::Scalar| (a <= p && p <= b) || (b <= p && p <= a);
+    between(a.x(), b.x(), p.x()) && between(a.y(), b.y(), p.y())
+}
+
+/// Common point of segments (`a`, `b`) and (`c`, `d`), if any. `touch` is `true` if segments
+/// only share an endpoint of both.
+pub(crate) fn intersection<P: Point>(a: P, b: P, c: P, d: P) -> Option<(P, bool)> {
+    let (o1, o2) = (P::orient(a, b, c), P::orient(a, b, d));
+    let (o3, o4) = (P::orient(c, d, a), P::orient(c, d, b));
+    let opposite =
+        |p: Ordering, q: Ordering| p != Ordering::Equal && q != Ordering::Equal && p != q;
+    if opposite(o1, o2) && opposite(o3, o4) {
+        // Proper crossing, point is computed approximately.
+        let f = |p: P| [p.x().to_f64(), p.y().to_f64()];
+        let (a, b, c, d) = (f(a), f(b), f(c), f(d));
+        let cross = |p: [f64; 2]| (d[0] - c[0]) * (p[1] - c[1]) - (d[1] - c[1]) * (p[0] - c[0]);
+        let (da, db) = (cross(a), cross(b));
+        let t = da / (da - db);
+        let along = |k: usize| P::Scalar::from_f64(a[k] + t * (b[k] - a[k]));
+        return Some((P::from_xy(along(0), along(1)), false));
+    }
+    let candidates = [
+        (o3 == Ordering::Equal, a, c, d),
+        (o4 == Ordering::Equal, b, c, d),
+        (o1 == Ordering::Equal, c, a, b),
+        (o2 == Ordering::Equal, d, a, b),
+    ];
+    let collinear = o1 == Ordering::Equal && o2 == Ordering::Equal;
+    let mut touch = None;
+    for &(on_line, p, from, to) in &candidates {
+        if on_line && on_segment(from, to, p) {
+            if p != from && p != to {
+                return Some((p, false));
+            }
+            touch = Some(p);
+        }
+    }
+    // Overlap of collinear segments with equal endpoints.
+    let overlap = collinear && touch.is_some() && ((a == c && b == d) || (a == d && b == c));
+    touch.map(|p| (p, !overlap))
+}
+
+impl<P: Point> Outline<P> {
+    /// Fallible version of [`Outline::new`]. Fails if [`Outline::validate`] finds any problem.
+    pub fn try_new(vertices: impl Iterator<Item = P>) -> Result<Self, OutlineError<P>> {
+        let outline = Outline::new(vertices);
+        let report = outline.validate();
+        if report.is_valid() {
+            Ok(outline)
+        } else {
+            Err(OutlineError { report })
+        }
+    }
+
+    /// Finds every problem of outline. Outline without issues is simple counter-clockwise
+    /// ring with at least three vertices. If some vertex isn't finite, geometric checks are
+    /// skipped. Takes `O((n + k) log n)` time for `k` pairs of intersecting edges.
+    pub fn validate(&self) -> ValidationReport<P> {
+        let mut issues = Vec::new();
+        let n = self.len();
+        if n < 3 {
+            issues.push(OutlineIssue::TooFewVertices(n));
+        }
+        let finite = |v: P| v.x().to_f64().is_finite() && v.y().to_f64().is_finite();
+        let non_finite = self
+            .vertices()
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| !finite(v));
+        issues.extend(non_finite.map(|(i, _)| OutlineIssue::NonFinite(i)));
+        if n == 0 || issues.len() > usize::from(n < 3) {
+            return ValidationReport { issues };
+        }
+
+        let vertices = self.vertices();
+        // Equal vertices are neighbors in lexicographic order.
+        let mut sorted: Vec<usize> = (0..n).collect();
+        let key = |i: usize| (vertices[i].x().to_f64(), vertices[i].y().to_f64());
+        sorted.sort_by(|&i, &j| key(i).partial_cmp(&key(j)).unwrap_or(Ordering::Equal));
+        let mut duplicates = Vec::new();
+        let mut start = 0;
+        while start < n {
+            let mut end = start + 1;
+            while end < n && vertices[sorted[end - 1]] == vertices[sorted[end]] {
+                end += 1;
+            }
+            let group = &sorted[start..end];
+            for (k, &i) in group.iter().enumerate() {
+                for &j in &group[k + 1..] {
+                    let (i, j) = (i.min(j), i.max(j));
+                    if j >= i + 2 && !(i == 0 && j == n - 1) {
+                        duplicates.push((i, j));
+                    }
+                }
+            }
+            start = end;
+        }
+        duplicates.sort_unstable();
+        issues.extend(
+            duplicates
+                .into_iter()
+                .map(|(i, j)| OutlineIssue::DuplicateVertex(i, j)),
+        );
+        let zero_edges = (0..n).filter(|&i| self[i as isize] == self[i as isize + 1]);
+        issues.extend(zero_edges.map(OutlineIssue::ZeroLengthEdge));
+        for i in 0..n {
+            let (prev, that, next) = self.prev_that_next(i as isize);
+            if prev != that
+                && next != that
+                && P::orient(prev, that, next) == Ordering::Equal
+                && same_direction(that, prev, next)
+            {
+                issues.push(OutlineIssue::Spike(i));
+            }
+        }
+
+        let mut crossings: Vec<_> = self
+            .self_intersections()
+            .into_iter()
+            .map(|found| (found.edges.0.edge, found.edges.1.edge))
+            .collect();
+        crossings.sort_unstable();
+        for (i, j) in crossings {
+            let (a, b) = (vertices[i], vertices[i + 1]);
+            let (c, d) = (vertices[j], vertices[(j + 1) % n]);
+            if let Some((point, touch)) = intersection(a, b, c, d) {
+                // Touch at equal vertices is reported as duplicate vertex.
+                if !touch {
+                    issues.push(OutlineIssue::SelfIntersection {
+                        edges: (i, j),
+                        point,
+                    });
+                }
+            }
+        }
+
+        if n >= 3 {
+            let orientation = self.orientation();
+            if orientation != Orientation::CounterClockwise {
+                issues.push(OutlineIssue::WrongOrientation(orientation));
+            }
+        }
+        ValidationReport { issues }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutlineIssue;
+    use crate::outline::{Orientation, Outline};
+    use glam::Vec2;
+
+    fn outline(points: &[(f32, f32)]) -> Outline {
+        Outline::new(points.iter().map(|&(x, y)| Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn valid() {
+        let square = outline(&[(0f32, 0f32), (1f32, 0f32), (1f32, 1f32), (0f32, 1f32)]);
+        assert!(square.validate().is_valid());
+        let verts = square.vertices().to_vec();
+        assert_eq!(Outline::try_new(verts.into_iter()), Ok(square));
+    }
+
+    #[test]
+    fn degenerate() {
+        let empty = Outline::<Vec2>::try_new(std::iter::empty()).unwrap_err();
+        assert_eq!(empty.report().issues(), &[OutlineIssue::TooFewVertices(0)]);
+        assert_eq!(
+            empty.to_string(),
+            "invalid outline: outline has only 0 vertices"
+        );
+
+        let nan = outline(&[(0f32, 0f32), (f32::NAN, 0f32), (1f32, f32::INFINITY)]);
+        assert_eq!(
+            nan.validate().issues(),
+            &[OutlineIssue::NonFinite(1), OutlineIssue::NonFinite(2)]
+        );
+
+        let line = outline(&[(0f32, 0f32), (1f32, 1f32)]);
+        assert_eq!(
+            line.validate().issues(),
+            &[
+                OutlineIssue::TooFewVertices(2),
+                OutlineIssue::Spike(0),
+                OutlineIssue::Spike(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicates_and_spikes() {
+        // Zero-length edge 1 and spike at vertex 4 going back to (2, 1).
+        let outline = outline(&[
+            (0f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 0f32),
+            (2f32, 2f32),
+            (2f32, 1f32),
+            (0f32, 1f32),
+        ]);
+        let report = outline.validate();
+        assert_eq!(
+            report.issues(),
+            &[
+                OutlineIssue::ZeroLengthEdge(1),
+                OutlineIssue::Spike(3),
+                OutlineIssue::SelfIntersection {
+                    edges: (2, 4),
+                    point: Vec2::new(2f32, 1f32),
+                },
+            ]
+        );
+
+        // Ring touches itself at vertex (1, 1).
+        let bowtie =
+            Outline::new(vec![[0i64, 0], [1, 1], [2, 0], [2, 2], [1, 1], [0, 2]].into_iter());
+        assert_eq!(
+            bowtie.validate().issues(),
+            &[OutlineIssue::DuplicateVertex(1, 4)]
+        );
+    }
+
+    #[test]
+    fn self_intersection() {
+        let figure_eight = outline(&[(0f32, 0f32), (2f32, 2f32), (2f32, 0f32), (0f32, 2f32)]);
+        let report = figure_eight.validate();
+        assert_eq!(
+            report.issues(),
+            &[
+                OutlineIssue::SelfIntersection {
+                    edges: (0, 2),
+                    point: Vec2::new(1f32, 1f32),
+                },
+                OutlineIssue::WrongOrientation(Orientation::Degenerate),
+            ]
+        );
+
+        let integer = Outline::new(vec![[0i32, 0], [3, 3], [3, 0], [0, 2]].into_iter());
+        match integer.validate().issues()[0] {
+            OutlineIssue::SelfIntersection { edges, point } => {
+                assert_eq!(edges, (0, 2));
+                assert_eq!(point, [1, 1]);
+            }
+            issue => panic!("unexpected issue {:?}", issue),
+        }
+    }
+
+    #[test]
+    fn orientation() {
+        let clockwise = outline(&[(0f32, 0f32), (0f32, 1f32), (1f32, 1f32), (1f32, 0f32)]);
+        assert_eq!(
+            clockwise.validate().issues(),
+            &[OutlineIssue::WrongOrientation(Orientation::Clockwise)]
+        );
+    }
+}